@@ -0,0 +1,95 @@
+/* Generated from src/ffi.rs by cbindgen, do not edit.
+ * Regenerate with `cbindgen --config cbindgen.toml --output include/sharks.h`. */
+
+#ifndef SHARKS_H
+#define SHARKS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Result codes of the C API.
+typedef enum {
+  // The call succeeded.
+  SHARKS_ERROR_OK = 0,
+  // A required pointer argument was null.
+  SHARKS_ERROR_NULL_POINTER = 1,
+  // An argument was out of range, such as an empty secret or more shares than the field allows.
+  SHARKS_ERROR_INVALID_ARGUMENT = 2,
+  // There were less distinct shares than the threshold.
+  SHARKS_ERROR_NOT_ENOUGH_SHARES = 3,
+  // The shares were inconsistent with each other, such as having different lengths.
+  SHARKS_ERROR_INCONSISTENT_SHARES = 4,
+  // The bytes could not be deserialized into a share.
+  SHARKS_ERROR_INVALID_SHARE = 5,
+} SharksError;
+
+// Opaque handle to a share.
+typedef struct SharksShare SharksShare;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Splits the `secret_len` bytes at `secret` into `count` shares, any `threshold` of which recover the secret,
+// and writes their handles to the `shares_out` array, which must have room for `count` handles.
+//
+// # Safety
+//
+// `secret` must point to `secret_len` readable bytes, and `shares_out` to `count` writable handles.
+SharksError sharks_split(uint8_t threshold,
+                         const uint8_t *secret,
+                         size_t secret_len,
+                         size_t count,
+                         SharksShare **shares_out);
+
+// Recovers the secret from the `count` handles of the `shares` array, with the given minimum `threshold`,
+// which can not be zero.
+// On success, writes a buffer with the secret to `secret_out` and its length to `secret_len_out`,
+// to be released with `sharks_bytes_free`.
+//
+// # Safety
+//
+// `shares` must point to `count` valid handles, and `secret_out` and `secret_len_out` must be writable.
+SharksError sharks_combine(uint8_t threshold,
+                           const SharksShare *const *shares,
+                           size_t count,
+                           uint8_t **secret_out,
+                           size_t *secret_len_out);
+
+// Serializes a share as its `x` byte followed by its `y` bytes. On success, writes the buffer to `bytes_out`
+// and its length to `len_out`, to be released with `sharks_bytes_free`.
+//
+// # Safety
+//
+// `share` must be a valid handle, and `bytes_out` and `len_out` must be writable.
+SharksError sharks_share_to_bytes(const SharksShare *share,
+                                  uint8_t **bytes_out,
+                                  size_t *len_out);
+
+// Deserializes a share from the `len` bytes at `bytes`, as written by `sharks_share_to_bytes`.
+// On success, writes its handle to `share_out`, to be released with `sharks_share_free`.
+//
+// # Safety
+//
+// `bytes` must point to `len` readable bytes, and `share_out` must be writable.
+SharksError sharks_share_from_bytes(const uint8_t *bytes, size_t len, SharksShare **share_out);
+
+// Wipes and releases a share handle. Null handles are ignored.
+//
+// # Safety
+//
+// `share` must be null or a handle returned by this API, not released yet.
+void sharks_share_free(SharksShare *share);
+
+// Wipes and releases a buffer returned by this API. Null buffers are ignored.
+//
+// # Safety
+//
+// `bytes` must be null or a buffer returned by this API along with its `len`, not released yet.
+void sharks_bytes_free(uint8_t *bytes, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SHARKS_H */
@@ -0,0 +1,238 @@
+use alloc::vec::Vec;
+
+use super::error::Error;
+use super::field::GF256;
+use super::memory::SecretVec;
+use super::secret::Secret;
+use super::share::Share;
+
+/// Tuple struct which implements additive n-of-n secret sharing over the 256 bits Galois Field defined by
+/// the `P` reduction polynomial, 0x11d by default as in `Sharks`.
+/// Its only parameter is the number of shares, all of which are needed to recover the secret.
+///
+/// There are no polynomials involved: all shares but one are random, and the last one is chosen
+/// so that they all add up to the secret. In every 256 bits Galois Field addition is a XOR, so the
+/// shares are the same whatever the reduction polynomial.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ AdditiveSharks, Share };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // Two-person rule
+/// let sharks: AdditiveSharks = AdditiveSharks(2);
+/// // Obtain an iterator over the 2 shares for secret [1, 2, 3, 4]
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let shares: Vec<Share> = sharks.dealer_rng(&[1, 2, 3, 4], &mut rng).collect();
+/// // Recover the original secret!
+/// let secret = sharks.recover(&shares).unwrap();
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+/// ```
+pub struct AdditiveSharks<const P: u16 = 0x11d>(pub u8);
+
+impl<const P: u16> AdditiveSharks<P> {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along exactly `n` new shares.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ AdditiveSharks, Share };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks: AdditiveSharks = AdditiveSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(&[1, 2], &mut rng);
+    /// // Get the 3 shares
+    /// let shares: Vec<Share> = dealer.collect();
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> impl Iterator<Item = Share> {
+        let mut last: SecretVec<GF256<P>> = secret.iter().map(|b| GF256(*b)).collect();
+        let mut shares = Vec::with_capacity(self.0 as usize);
+
+        for x in 1..self.0 {
+            let y: SecretVec<GF256> = (0..secret.len()).map(|_| GF256(rng.gen())).collect();
+            for (l, r) in last.iter_mut().zip(y.iter()) {
+                *l = l.clone() - GF256(r.0);
+            }
+            shares.push(Share { x: GF256(x), y });
+        }
+
+        if self.0 > 0 {
+            shares.push(Share {
+                x: GF256(self.0),
+                y: last.iter().map(|l| GF256(l.0)).collect(),
+            });
+        }
+
+        shares.into_iter()
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along exactly `n` new shares.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ AdditiveSharks, Share };
+    /// # let sharks: AdditiveSharks = AdditiveSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let dealer = sharks.dealer(&[1, 2]);
+    /// // Get the 3 shares
+    /// let shares: Vec<Share> = dealer.collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> impl Iterator<Item = Share> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret.
+    /// Exact duplicates are skipped, but shares at the same `x` with different `y` are rejected.
+    /// If fewer or more than `n` distinct shares are given, an `Err` is returned,
+    /// otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ AdditiveSharks, Share };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks: AdditiveSharks = AdditiveSharks(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<Share> = sharks.dealer_rng(&[1], &mut rng).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove a share for demonstration purposes
+    /// shares.pop();
+    /// secret = sharks.recover(&shares);
+    /// // A share is missing
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, Error>
+    where
+        T: IntoIterator<Item = &'a Share>,
+        T::IntoIter: Iterator<Item = &'a Share>,
+    {
+        let mut share_length: Option<usize> = None;
+        let mut values: Vec<&Share> = Vec::new();
+
+        for share in shares.into_iter() {
+            if share_length.is_none() {
+                share_length = Some(share.y.len());
+            }
+
+            if Some(share.y.len()) != share_length {
+                return Err(Error::InconsistentShares);
+            }
+            match values.iter().find(|s| s.x == share.x) {
+                Some(s) if s.y[..] != share.y[..] => return Err(Error::ConflictingShares),
+                Some(_) => {}
+                None => values.push(share),
+            }
+        }
+
+        if values.is_empty() || values.len() < self.0 as usize {
+            Err(Error::NotEnoughShares)
+        } else if values.len() > self.0 as usize {
+            Err(Error::TooManyShares)
+        } else {
+            Ok(Secret(
+                (0..values[0].y.len())
+                    .map(|i| {
+                        values
+                            .iter()
+                            .map(|s| GF256::<P>(s.y[i].0))
+                            .sum::<GF256<P>>()
+                            .0
+                    })
+                    .collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdditiveSharks, Error, Share};
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn make_shares<const P: u16>(sharks: &AdditiveSharks<P>, secret: &[u8]) -> Vec<Share> {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        sharks.dealer_rng(secret, &mut rng).collect()
+    }
+
+    #[test]
+    fn test_missing_share_err() {
+        let sharks: AdditiveSharks = AdditiveSharks(5);
+        let mut shares = make_shares(&sharks, &[1, 2]);
+        assert_eq!(shares.len(), 5);
+        shares[4] = shares[3].clone();
+        assert_eq!(sharks.recover(&shares).err(), Some(Error::NotEnoughShares));
+        assert_eq!(
+            sharks.recover(&shares[..4]).err(),
+            Some(Error::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn test_too_many_shares_err() {
+        let sharks: AdditiveSharks = AdditiveSharks(2);
+        let shares = make_shares(&AdditiveSharks::<0x11d>(3), &[1, 2]);
+        assert_eq!(sharks.recover(&shares).err(), Some(Error::TooManyShares));
+    }
+
+    #[test]
+    fn test_inconsistent_shares_err() {
+        let sharks: AdditiveSharks = AdditiveSharks(2);
+        let mut shares = make_shares(&sharks, &[1, 2]);
+        shares[1].y.pop();
+        assert_eq!(
+            sharks.recover(&shares).err(),
+            Some(Error::InconsistentShares)
+        );
+    }
+
+    #[test]
+    fn test_conflicting_shares_err() {
+        let sharks: AdditiveSharks = AdditiveSharks(2);
+        let shares = make_shares(&sharks, &[1, 2]);
+        let duplicated = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert_eq!(
+            sharks.recover(&duplicated).unwrap().expose_secret(),
+            &[1, 2]
+        );
+        let conflicting = [shares[0].clone(), &shares[0] * 2, shares[1].clone()];
+        assert_eq!(
+            sharks.recover(&conflicting).err(),
+            Some(Error::ConflictingShares)
+        );
+    }
+
+    #[test]
+    fn test_other_field_works() {
+        let sharks = AdditiveSharks::<0x11b>(3);
+        let shares = make_shares(&sharks, &[1, 2, 3, 4]);
+        assert_eq!(
+            sharks.recover(&shares).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let sharks: AdditiveSharks = AdditiveSharks(255);
+        let shares = make_shares(&sharks, &[1, 2, 3, 4]);
+        assert_eq!(
+            sharks.recover(&shares).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+        let sharks: AdditiveSharks = AdditiveSharks(1);
+        let shares = make_shares(&sharks, &[1, 2, 3, 4]);
+        assert_eq!(
+            sharks.recover(&shares).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+// Interactive key ceremony: participants enter their shares one at a time on the same terminal,
+// each one checked as soon as it is entered, until the threshold is met and the secret is recovered.
+
+use std::convert::TryFrom;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::OnceLock;
+
+use sharks::{Secret, ShareEnvelope, Sharks};
+use zeroize::Zeroizing;
+
+use super::encoding::Encoding;
+
+// Clears the screen and the scrollback, and moves the cursor home
+const WIPE: &[u8] = b"\x1b[H\x1b[2J\x1b[3J";
+
+// Terminal settings to restore, and whether to wipe the screen, if the ceremony is interrupted by a signal
+#[cfg(unix)]
+static ORIGINAL: OnceLock<libc::termios> = OnceLock::new();
+#[cfg(unix)]
+static WIPE_ON_SIGNAL: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+const SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+// Only calls async-signal-safe functions, then lets the signal terminate the process as it would have
+#[cfg(unix)]
+extern "C" fn interrupted(signal: libc::c_int) {
+    unsafe {
+        if let Some(original) = ORIGINAL.get() {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+        }
+        if WIPE_ON_SIGNAL.load(Ordering::SeqCst) {
+            libc::write(
+                libc::STDOUT_FILENO,
+                WIPE.as_ptr() as *const libc::c_void,
+                WIPE.len(),
+            );
+        }
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+// Disables the echo of the terminal on standard input while alive, and wipes the screen when dropped,
+// however the ceremony ends. Both also happen when the process is interrupted by a signal.
+struct Session {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+    wipe: bool,
+}
+
+impl Session {
+    fn new() -> Self {
+        // Nothing sensitive is echoed, but the secret may be shown
+        let wipe = io::stdout().is_terminal();
+        #[cfg(unix)]
+        {
+            WIPE_ON_SIGNAL.store(wipe, Ordering::SeqCst);
+            let mut termios = unsafe { core::mem::zeroed::<libc::termios>() };
+            let original = if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } == 0 {
+                let _ = ORIGINAL.set(termios);
+                Some(termios)
+            } else {
+                None
+            };
+            for signal in SIGNALS.iter() {
+                unsafe { libc::signal(*signal, interrupted as *const () as libc::sighandler_t) };
+            }
+            if original.is_some() {
+                let mut hidden = termios;
+                hidden.c_lflag &= !libc::ECHO;
+                hidden.c_lflag |= libc::ECHONL;
+                unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &hidden) };
+            }
+            Session { original, wipe }
+        }
+        #[cfg(not(unix))]
+        Session { wipe }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = self.original {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original) };
+        }
+        if self.wipe {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(WIPE).and_then(|_| stdout.flush());
+        }
+        #[cfg(unix)]
+        for signal in SIGNALS.iter() {
+            unsafe { libc::signal(*signal, libc::SIG_DFL) };
+        }
+    }
+}
+
+// Reads a line, returning `None` at the end of the input. Lines hold shares, so they are wiped on drop.
+fn read_line(input: &mut impl BufRead) -> Result<Option<Zeroizing<String>>, String> {
+    let mut line = Zeroizing::new(String::new());
+    match input.read_line(&mut line) {
+        Ok(0) => Ok(None),
+        Ok(_) => Ok(Some(Zeroizing::new(line.trim().to_owned()))),
+        Err(e) => Err(format!("Can not read share: {}", e)),
+    }
+}
+
+fn say(stdout: &mut impl Write, message: &[u8]) -> Result<(), String> {
+    stdout
+        .write_all(message)
+        .and_then(|_| stdout.flush())
+        .map_err(|e| format!("Can not write to the terminal: {}", e))
+}
+
+// Checks an entered share against the threshold and the accepted ones, returning why it is rejected if so.
+// Typos are caught by the envelope checksum.
+fn check(
+    text: &str,
+    encoding: Encoding,
+    threshold: u8,
+    accepted: &[ShareEnvelope],
+) -> Result<ShareEnvelope, String> {
+    let bytes = Secret::from(
+        encoding
+            .decode(text.as_bytes())
+            .map_err(|e| format!("{}, it may have a typo", e))?,
+    );
+    let envelope = ShareEnvelope::try_from(bytes.expose_secret())?;
+    envelope.verify_digest()?;
+    let share = &envelope.share;
+
+    if envelope.threshold != threshold {
+        return Err(format!(
+            "Share was dealt with a threshold of {} instead of {}",
+            envelope.threshold, threshold
+        ));
+    }
+    if let Some(first) = accepted.first() {
+        if first.set_id != envelope.set_id || first.digests != envelope.digests {
+            return Err("Share belongs to another set than the first one".into());
+        }
+    }
+    match accepted.iter().find(|e| e.share.x == share.x) {
+        Some(e) if e.share.y[..] == share.y[..] => {
+            Err(format!("Share x = {} was already entered", share.x.0))
+        }
+        Some(_) => Err(format!(
+            "Share conflicts with the one entered for x = {}",
+            share.x.0
+        )),
+        None => Ok(envelope),
+    }
+}
+
+pub fn run(sharks: Sharks, encoding: Encoding, output: Option<&Path>) -> Result<(), String> {
+    if !encoding.is_text() {
+        return Err("Shares must be entered with a text encoding".into());
+    }
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut stdout = io::stdout();
+    let threshold = sharks.0 as usize;
+    let mut accepted: Vec<ShareEnvelope> = Vec::new();
+    // Echo is disabled before the first prompt, so that nothing typed early is shown
+    let session = Session::new();
+
+    say(
+        &mut stdout,
+        format!(
+            "Key ceremony: {} shares are needed to recover the secret.\n\
+             Shares are not shown as they are typed.\n",
+            threshold
+        )
+        .as_bytes(),
+    )?;
+
+    while accepted.len() < threshold {
+        say(
+            &mut stdout,
+            format!("Share {} of {}: ", accepted.len() + 1, threshold).as_bytes(),
+        )?;
+        let text = match read_line(&mut input)? {
+            Some(text) => text,
+            None => return Err("Ceremony aborted before the threshold was met".into()),
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        match check(&text, encoding, sharks.0, &accepted) {
+            Ok(envelope) => {
+                let x = envelope.share.x.0;
+                accepted.push(envelope);
+                say(
+                    &mut stdout,
+                    format!(
+                        "  Accepted share x = {} ({}/{})\n",
+                        x,
+                        accepted.len(),
+                        threshold
+                    )
+                    .as_bytes(),
+                )?;
+            }
+            Err(e) => say(&mut stdout, format!("  Rejected: {}\n", e).as_bytes())?,
+        }
+    }
+
+    let secret = sharks
+        .recover(accepted.iter().map(|e| &e.share))
+        .map_err(|e| e.to_string())?;
+    match output {
+        Some(path) => {
+            super::create_private(path)
+                .and_then(|mut f| f.write_all(secret.expose_secret()))
+                .map_err(|e| format!("Can not write {}: {}", path.display(), e))?;
+            say(
+                &mut stdout,
+                format!("Secret recovered and written to {}\n", path.display()).as_bytes(),
+            )?;
+        }
+        None => {
+            let shown = Secret::from(encoding.encode(secret.expose_secret()));
+            let message = Zeroizing::new(format!(
+                "Secret recovered:\n{}\nPress Enter to clear the screen.",
+                String::from_utf8_lossy(shown.expose_secret())
+            ));
+            say(&mut stdout, message.as_bytes())?;
+            read_line(&mut input)?;
+        }
+    }
+    drop(session);
+    Ok(())
+}
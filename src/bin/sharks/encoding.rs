@@ -0,0 +1,141 @@
+// Text encodings of the shares, so that they can be printed, pasted or sent along with other text.
+
+use clap::ValueEnum;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Encoding {
+    /// Raw bytes, one share per file
+    Raw,
+    /// Lowercase hexadecimal, one share per line
+    Hex,
+    /// Standard base64 with padding, one share per line
+    Base64,
+}
+
+impl Encoding {
+    pub fn is_text(self) -> bool {
+        self != Encoding::Raw
+    }
+
+    // Extension of the share files written by `split`
+    pub fn extension(self) -> &'static str {
+        match self {
+            Encoding::Raw => "bin",
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "b64",
+        }
+    }
+
+    pub fn encode(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Encoding::Raw => bytes.to_vec(),
+            Encoding::Hex => bytes
+                .iter()
+                .flat_map(|b| format!("{:02x}", b).into_bytes())
+                .collect(),
+            Encoding::Base64 => encode_base64(bytes),
+        }
+    }
+
+    pub fn decode(self, text: &[u8]) -> Result<Vec<u8>, &'static str> {
+        match self {
+            Encoding::Raw => Ok(text.to_vec()),
+            Encoding::Hex => decode_hex(text),
+            Encoding::Base64 => decode_base64(text),
+        }
+    }
+}
+
+fn decode_hex(text: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if text.len() % 2 == 1 {
+        return Err("Hex share must have an even length");
+    }
+    text.chunks(2)
+        .map(|pair| {
+            core::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or("Share is not hex encoded")
+        })
+        .collect()
+}
+
+fn encode_base64(bytes: &[u8]) -> Vec<u8> {
+    let mut text = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(BASE64[(group >> (18 - 6 * i)) as usize & 0x3f]);
+            } else {
+                text.push(b'=');
+            }
+        }
+    }
+    text
+}
+
+fn decode_base64(text: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if !text.len().is_multiple_of(4) {
+        return Err("Base64 share must have a length multiple of 4");
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    for (n, chunk) in text.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && n != text.len() / 4 - 1) {
+            return Err("Invalid base64 padding");
+        }
+
+        let mut group = 0u32;
+        for c in &chunk[..4 - padding] {
+            let value = BASE64
+                .iter()
+                .position(|b| b == c)
+                .ok_or("Share is not base64 encoded")?;
+            group = (group << 6) | value as u32;
+        }
+        group <<= 6 * padding as u32;
+        bytes.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    #[test]
+    fn hex_works() {
+        assert_eq!(Encoding::Hex.encode(&[0x00, 0xab, 0x1f]), b"00ab1f");
+        assert_eq!(Encoding::Hex.decode(b"00AB1f").unwrap(), [0x00, 0xab, 0x1f]);
+        assert!(Encoding::Hex.decode(b"0ab").is_err());
+        assert!(Encoding::Hex.decode(b"0g").is_err());
+    }
+
+    #[test]
+    fn base64_works() {
+        for (bytes, text) in [
+            (&b""[..], &b""[..]),
+            (b"f", b"Zg=="),
+            (b"fo", b"Zm8="),
+            (b"foo", b"Zm9v"),
+            (b"foob", b"Zm9vYg=="),
+            (&[0xfb, 0xff], b"+/8="),
+        ]
+        .iter()
+        {
+            assert_eq!(Encoding::Base64.encode(bytes), *text);
+            assert_eq!(Encoding::Base64.decode(text).unwrap(), *bytes);
+        }
+        assert!(Encoding::Base64.decode(b"Zm9").is_err());
+        assert!(Encoding::Base64.decode(b"Zg==Zm9v").is_err());
+        assert!(Encoding::Base64.decode(b"Z===").is_err());
+        assert!(Encoding::Base64.decode(b"Zm9-").is_err());
+    }
+}
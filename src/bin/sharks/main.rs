@@ -0,0 +1,342 @@
+//! `sharks` command-line tool, which splits a secret file into shares and combines them back.
+//! Shares are written in `ShareEnvelope`s, so that they can be audited without recovering the secret.
+//!
+//! ```text
+//! sharks split -k 3 -n 5 secret.txt -o shares/
+//! sharks combine shares/* > secret.txt
+//! sharks inspect shares/*
+//! sharks verify shares/*
+//! sharks ceremony -k 3 -o secret.txt
+//! ```
+
+mod ceremony;
+mod encoding;
+
+use std::convert::TryFrom;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use sharks::{Secret, ShareEnvelope, Sharks};
+
+use encoding::Encoding;
+
+#[derive(Parser)]
+#[command(name = "sharks", version, about = "Shamir's Secret Sharing of files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Splits a secret into shares
+    Split {
+        /// Minimum number of shares needed to recover the secret
+        #[arg(short = 'k', long, value_parser = clap::value_parser!(u8).range(1..))]
+        threshold: u8,
+        /// Number of shares to generate
+        #[arg(short = 'n', long, value_parser = clap::value_parser!(u8).range(1..))]
+        shares: u8,
+        /// File with the secret, or `-` to read it from standard input
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// Directory to write the shares to, one file each. Text shares are printed if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Encoding of the shares
+        #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+        encoding: Encoding,
+    },
+    /// Combines shares to recover the secret
+    Combine {
+        /// Share files, or `-` to read text shares from standard input, one per line
+        #[arg(default_value = "-")]
+        inputs: Vec<PathBuf>,
+        /// Minimum number of shares needed to recover the secret, checked against the one the shares were dealt
+        /// with. Defaults to it
+        #[arg(short = 'k', long, value_parser = clap::value_parser!(u8).range(1..))]
+        threshold: Option<u8>,
+        /// File to write the secret to, instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Encoding of the shares
+        #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+        encoding: Encoding,
+        /// Write the secret to standard output even if it is a terminal
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Prints the version, set ID, threshold, `x` and length of each share
+    Inspect {
+        /// Share files, or `-` to read text shares from standard input, one per line
+        #[arg(default_value = "-")]
+        inputs: Vec<PathBuf>,
+        /// Encoding of the shares
+        #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+        encoding: Encoding,
+    },
+    /// Checks the checksums and digests of shares, and that they belong to the same set and meet the threshold,
+    /// without recovering the secret. Whether the shares lie on the same polynomial can only be checked
+    /// when there are more of them than the threshold
+    Verify {
+        /// Share files, or `-` to read text shares from standard input, one per line
+        #[arg(default_value = "-")]
+        inputs: Vec<PathBuf>,
+        /// Minimum number of shares needed to recover the secret, checked against the one the shares were dealt
+        /// with. Defaults to it
+        #[arg(short = 'k', long, value_parser = clap::value_parser!(u8).range(1..))]
+        threshold: Option<u8>,
+        /// Encoding of the shares
+        #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+        encoding: Encoding,
+    },
+    /// Walks participants through entering their shares on the terminal, then recovers the secret
+    Ceremony {
+        /// Minimum number of shares needed to recover the secret
+        #[arg(short = 'k', long, value_parser = clap::value_parser!(u8).range(1..))]
+        threshold: u8,
+        /// File to write the secret to. It is shown on the terminal until Enter is pressed if omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Encoding of the shares, and of the secret when shown
+        #[arg(short, long, value_enum, default_value_t = Encoding::Hex)]
+        encoding: Encoding,
+    },
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+fn read_input(path: &Path) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if is_stdio(path) {
+        io::stdin().read_to_end(&mut bytes)
+    } else {
+        File::open(path).and_then(|mut f| f.read_to_end(&mut bytes))
+    }
+    .map_err(|e| format!("Can not read {}: {}", path.display(), e))?;
+    Ok(bytes)
+}
+
+// Creates a file only readable by its owner, never overwriting an existing one
+fn create_private(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+fn split(
+    sharks: Sharks,
+    count: u8,
+    input: &Path,
+    output: Option<&Path>,
+    encoding: Encoding,
+) -> Result<(), String> {
+    if count < sharks.0 {
+        return Err("The number of shares must be at least the threshold".into());
+    }
+    let secret = Secret::from(read_input(input)?);
+    if secret.expose_secret().is_empty() {
+        return Err("Secret can not be empty".into());
+    }
+
+    let shares = sharks
+        .dealer_envelopes(secret.expose_secret(), count)?
+        .into_iter()
+        .map(|envelope| (envelope.share.x.0, encoding.encode(&Vec::from(&envelope))));
+
+    match output {
+        Some(dir) => {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Can not create {}: {}", dir.display(), e))?;
+            for (x, share) in shares {
+                let path = dir.join(format!("share-{:03}.{}", x, encoding.extension()));
+                create_private(&path)
+                    .and_then(|mut f| f.write_all(&share))
+                    .map_err(|e| format!("Can not write {}: {}", path.display(), e))?;
+            }
+        }
+        None if encoding.is_text() => {
+            let mut stdout = io::stdout().lock();
+            for (_, share) in shares {
+                stdout
+                    .write_all(&share)
+                    .and_then(|_| stdout.write_all(b"\n"))
+                    .map_err(|e| format!("Can not write shares: {}", e))?;
+            }
+        }
+        None => return Err("Raw shares must be written to a directory with --output".into()),
+    }
+    Ok(())
+}
+
+// Reads the share envelopes of the given inputs, along with the input and line they were found at
+fn read_shares(
+    inputs: &[PathBuf],
+    encoding: Encoding,
+) -> Result<Vec<(String, ShareEnvelope)>, String> {
+    let mut shares = Vec::new();
+    for path in inputs {
+        let contents = read_input(path)?;
+        let encoded: Vec<(String, &[u8])> = if encoding.is_text() {
+            contents
+                .split(|b| *b == b'\n')
+                .enumerate()
+                .map(|(i, line)| (format!("{}:{}", path.display(), i + 1), line.trim_ascii()))
+                .filter(|(_, line)| !line.is_empty())
+                .collect()
+        } else {
+            vec![(path.display().to_string(), contents.as_slice())]
+        };
+
+        for (source, share) in encoded {
+            let bytes = encoding
+                .decode(share)
+                .map_err(|e| format!("{}: {}", source, e))?;
+            let share = ShareEnvelope::try_from(bytes.as_slice())
+                .map_err(|e| format!("{}: {}", source, e))?;
+            shares.push((source, share));
+        }
+    }
+    Ok(shares)
+}
+
+// The threshold given on the command line, or else the one the first share was dealt with
+fn sharks_for(threshold: Option<u8>, shares: &[(String, ShareEnvelope)]) -> Result<Sharks, String> {
+    match (threshold, shares.first()) {
+        (Some(threshold), _) => Ok(Sharks(threshold)),
+        (None, Some((_, envelope))) => Ok(Sharks(envelope.threshold)),
+        (None, None) => Err("No shares were given".into()),
+    }
+}
+
+fn combine(
+    threshold: Option<u8>,
+    inputs: &[PathBuf],
+    output: Option<&Path>,
+    encoding: Encoding,
+    force: bool,
+) -> Result<(), String> {
+    let shares = read_shares(inputs, encoding)?;
+    let sharks = sharks_for(threshold, &shares)?;
+    sharks.verify_envelopes(shares.iter().map(|(_, envelope)| envelope))?;
+    let secret = sharks
+        .recover(shares.iter().map(|(_, envelope)| &envelope.share))
+        .map_err(|e| e.to_string())?;
+
+    match output {
+        Some(path) if !is_stdio(path) => create_private(path)
+            .and_then(|mut f| f.write_all(secret.expose_secret()))
+            .map_err(|e| format!("Can not write {}: {}", path.display(), e)),
+        _ => {
+            let mut stdout = io::stdout().lock();
+            if stdout.is_terminal() && !force {
+                return Err(
+                    "Refusing to write the secret to a terminal, use --force to do it anyway"
+                        .into(),
+                );
+            }
+            stdout
+                .write_all(secret.expose_secret())
+                .and_then(|_| stdout.flush())
+                .map_err(|e| format!("Can not write the secret: {}", e))
+        }
+    }
+}
+
+fn set_id(envelope: &ShareEnvelope) -> String {
+    String::from_utf8_lossy(&Encoding::Hex.encode(&envelope.set_id)).into_owned()
+}
+
+fn inspect(inputs: &[PathBuf], encoding: Encoding) -> Result<(), String> {
+    for (source, envelope) in read_shares(inputs, encoding)? {
+        println!(
+            "{}: version = {}, set = {}, threshold = {} of {}, x = {}, length = {} bytes",
+            source,
+            ShareEnvelope::VERSION,
+            set_id(&envelope),
+            envelope.threshold,
+            envelope.digests.len(),
+            envelope.share.x.0,
+            envelope.share.y.len()
+        );
+    }
+    Ok(())
+}
+
+fn verify(threshold: Option<u8>, inputs: &[PathBuf], encoding: Encoding) -> Result<(), String> {
+    let shares = read_shares(inputs, encoding)?;
+    let sharks = sharks_for(threshold, &shares)?;
+    sharks.verify_envelopes(shares.iter().map(|(_, envelope)| envelope))?;
+
+    let mut xs: Vec<u8> = shares
+        .iter()
+        .map(|(_, envelope)| envelope.share.x.0)
+        .collect();
+    xs.sort_unstable();
+    xs.dedup();
+    println!(
+        "OK: {} distinct shares of {} bytes from set {}, checksums and digests valid, threshold {} met",
+        xs.len(),
+        shares[0].1.share.y.len(),
+        set_id(&shares[0].1),
+        sharks.0
+    );
+    if xs.len() == sharks.0 as usize {
+        eprintln!(
+            "Warning: with only {} shares, whether they lie on the same polynomial can not be checked",
+            xs.len()
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Split {
+            threshold,
+            shares,
+            input,
+            output,
+            encoding,
+        } => split(
+            Sharks(threshold),
+            shares,
+            &input,
+            output.as_deref(),
+            encoding,
+        ),
+        Command::Combine {
+            inputs,
+            threshold,
+            output,
+            encoding,
+            force,
+        } => combine(threshold, &inputs, output.as_deref(), encoding, force),
+        Command::Inspect { inputs, encoding } => inspect(&inputs, encoding),
+        Command::Verify {
+            inputs,
+            threshold,
+            encoding,
+        } => verify(threshold, &inputs, encoding),
+        Command::Ceremony {
+            threshold,
+            output,
+            encoding,
+        } => ceremony::run(Sharks(threshold), encoding, output.as_deref()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("sharks: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Dealerless joint random secret generation (distributed key generation).
+//!
+//! Each of the `n` parties picks its own random contribution, shares it with `random_polynomial`
+//! and sends one sub-share to every party. Each party then adds up the sub-shares it received,
+//! obtaining its share of the sum of all contributions. The resulting secret is never known to anyone,
+//! unless `threshold` parties recover it.
+//!
+//! Usage example:
+//! ```
+//! # use sharks::{ Sharks, Share };
+//! # use sharks::dkg::{ MemoryTransport, Party };
+//! # use rand_chacha::rand_core::SeedableRng;
+//! let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+//! // 5 parties generate a 32 bytes secret, any 3 of them can recover
+//! let parties: Vec<Party> = (1..=5).map(|id| Party::new(id, 3, 5, 32).unwrap()).collect();
+//! let mut transport = MemoryTransport::new();
+//! for party in &parties {
+//!     for message in party.deal_rng(&mut rng) {
+//!         transport.send(&message);
+//!     }
+//! }
+//! // Each party adds up the sub-shares it received
+//! let shares: Vec<Share> = parties
+//!     .iter()
+//!     .map(|p| p.finish(&transport.receive(p.id()).unwrap()).unwrap())
+//!     .collect();
+//! let secret = Sharks(3).recover(&shares[..3]).unwrap();
+//! assert_eq!(Sharks(3).recover(&shares[2..]).unwrap().expose_secret(), secret.expose_secret());
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+
+use super::field::GF256;
+use super::math;
+use super::memory::SecretVec;
+use super::share::Share;
+
+/// A sub-share of the contribution of party `from`, addressed to party `share.x`.
+/// Can be serialized to and from a byte array.
+#[derive(Clone)]
+pub struct DealingMessage {
+    pub from: u8,
+    pub share: Share,
+}
+
+/// A party of the distributed key generation. Party IDs go from 1 to the number of parties,
+/// and are the `x` of their final shares.
+pub struct Party {
+    id: u8,
+    threshold: u8,
+    parties: u8,
+    length: usize,
+}
+
+impl Party {
+    /// Creates party `id` among `parties` parties, which will generate a secret of `length` bytes
+    /// recoverable by `threshold` parties. Returns an `Err` unless `0 < threshold <= parties`,
+    /// `0 < id <= parties` and `length > 0`.
+    pub fn new(id: u8, threshold: u8, parties: u8, length: usize) -> Result<Self, &'static str> {
+        if threshold == 0 || threshold > parties {
+            Err("Threshold must be positive and not greater than the number of parties")
+        } else if id == 0 || id > parties {
+            Err("Party ID must be between 1 and the number of parties")
+        } else if length == 0 {
+            Err("Secret length can not be zero")
+        } else {
+            Ok(Self {
+                id,
+                threshold,
+                parties,
+                length,
+            })
+        }
+    }
+
+    /// The party ID.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Picks a random contribution and returns the messages with its sub-shares, one for each party,
+    /// including itself.
+    /// A random number generator has to be provided.
+    pub fn deal_rng<R: rand::Rng>(&self, rng: &mut R) -> Vec<DealingMessage> {
+        let mut polys = SecretVec::with_capacity(self.length * self.threshold as usize);
+
+        for _ in 0..self.length {
+            let contribution = GF256(rng.gen());
+            math::random_polynomial(contribution, self.threshold, rng, &mut polys);
+        }
+
+        math::get_evaluator(polys, self.threshold)
+            .take(self.parties as usize)
+            .map(|share| DealingMessage {
+                from: self.id,
+                share,
+            })
+            .collect()
+    }
+
+    /// Adds up the sub-shares received from every party, obtaining this party's share of the secret.
+    /// If a message is missing, duplicated, addressed to another party or has the wrong length,
+    /// an `Err` is returned.
+    pub fn finish<'a, T>(&self, messages: T) -> Result<Share, &'static str>
+    where
+        T: IntoIterator<Item = &'a DealingMessage>,
+        T::IntoIter: Iterator<Item = &'a DealingMessage>,
+    {
+        let mut received: BTreeMap<u8, &Share> = BTreeMap::new();
+
+        for message in messages.into_iter() {
+            if message.share.x.0 != self.id {
+                return Err("Message is addressed to another party");
+            } else if message.from == 0 || message.from > self.parties {
+                return Err("Message comes from an unknown party");
+            } else if message.share.y.len() != self.length {
+                return Err("Message has the wrong length");
+            } else if received.insert(message.from, &message.share).is_some() {
+                return Err("Party sent more than one message");
+            }
+        }
+
+        if received.len() != self.parties as usize {
+            Err("Messages from all parties are needed")
+        } else {
+            Share::affine(
+                received.values().map(|share| (1, *share)),
+                &vec![0; self.length],
+            )
+        }
+    }
+}
+
+/// An in-memory transport for tests and simulations, which delivers serialized messages
+/// to the party they are addressed to.
+#[derive(Default)]
+pub struct MemoryTransport {
+    queues: BTreeMap<u8, Vec<Vec<u8>>>,
+}
+
+impl MemoryTransport {
+    /// Creates an empty transport.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a message for the party it is addressed to.
+    pub fn send(&mut self, message: &DealingMessage) {
+        self.queues
+            .entry(message.share.x.0)
+            .or_default()
+            .push(Vec::from(message));
+    }
+
+    /// Takes all the messages queued for party `to`.
+    /// If any of them can not be deserialized an `Err` is returned.
+    pub fn receive(&mut self, to: u8) -> Result<Vec<DealingMessage>, &'static str> {
+        self.queues
+            .remove(&to)
+            .unwrap_or_default()
+            .iter()
+            .map(|bytes| DealingMessage::try_from(bytes.as_slice()))
+            .collect()
+    }
+}
+
+/// Obtains a byte vector from a `DealingMessage` instance
+impl From<&DealingMessage> for Vec<u8> {
+    fn from(m: &DealingMessage) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(m.share.y.len() + 2);
+        bytes.push(m.from);
+        bytes.extend(Vec::from(&m.share));
+        bytes
+    }
+}
+
+/// Obtains a `DealingMessage` instance from a byte slice
+impl TryFrom<&[u8]> for DealingMessage {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<DealingMessage, Self::Error> {
+        if s.len() < 3 {
+            Err("A DealingMessage must be at least 3 bytes long")
+        } else {
+            let share = Share::try_from(&s[1..])?;
+            Ok(DealingMessage { from: s[0], share })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DealingMessage, MemoryTransport, Party};
+    use crate::{Share, Sharks};
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn run(threshold: u8, parties: u8, length: usize) -> Vec<Share> {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let parties: Vec<Party> = (1..=parties)
+            .map(|id| Party::new(id, threshold, parties, length).unwrap())
+            .collect();
+        let mut transport = MemoryTransport::new();
+        for party in &parties {
+            for message in party.deal_rng(&mut rng) {
+                transport.send(&message);
+            }
+        }
+        parties
+            .iter()
+            .map(|p| p.finish(&transport.receive(p.id()).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn party_checks_parameters() {
+        assert!(Party::new(1, 0, 3, 1).is_err());
+        assert!(Party::new(1, 4, 3, 1).is_err());
+        assert!(Party::new(0, 2, 3, 1).is_err());
+        assert!(Party::new(4, 2, 3, 1).is_err());
+        assert!(Party::new(1, 2, 3, 0).is_err());
+        assert!(Party::new(1, 2, 3, 1).is_ok());
+    }
+
+    #[test]
+    fn finish_checks_messages() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let a = Party::new(1, 2, 2, 4).unwrap();
+        let b = Party::new(2, 2, 2, 4).unwrap();
+        let from_a = a.deal_rng(&mut rng);
+        let from_b = b.deal_rng(&mut rng);
+        assert!(a.finish(vec![&from_a[0]]).is_err());
+        assert!(a.finish(vec![&from_a[0], &from_a[0]]).is_err());
+        assert!(a.finish(vec![&from_a[0], &from_b[1]]).is_err());
+        assert!(a.finish(vec![&from_a[0], &from_b[0]]).is_ok());
+    }
+
+    #[test]
+    fn message_serialization_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let messages = Party::new(3, 2, 4, 2).unwrap().deal_rng(&mut rng);
+        let bytes = Vec::from(&messages[1]);
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(&bytes[..2], &[3, 2]);
+        let message = DealingMessage::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(Vec::from(&message), bytes);
+        assert!(DealingMessage::try_from(&[3, 2][..]).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let shares = run(4, 7, 32);
+        let secret = Sharks(4).recover(&shares[..4]).unwrap();
+        assert_eq!(secret.expose_secret().len(), 32);
+        assert_eq!(
+            Sharks(4).recover(&shares[3..]).unwrap().expose_secret(),
+            secret.expose_secret()
+        );
+        assert!(Sharks(4).recover(&shares[4..]).is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::share::Share;
+use super::Sharks;
+
+const SET_ID_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 16;
+const DIGEST_LENGTH: usize = 32;
+const CHECKSUM_LENGTH: usize = 4;
+// Version, set ID, threshold, number of shares, `x` and nonce
+const HEADER_LENGTH: usize = 1 + SET_ID_LENGTH + 1 + 1 + 1 + NONCE_LENGTH;
+const DIGEST_DOMAIN: &[u8] = b"sharks share digest v1";
+
+// Digest of the share at `x`, salted with the set ID and the share's own nonce so that it hides the share
+fn digest(set_id: &[u8; SET_ID_LENGTH], nonce: &[u8; NONCE_LENGTH], share: &Share) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(DIGEST_DOMAIN);
+    hasher.update(set_id);
+    hasher.update([share.x.0]);
+    hasher.update(nonce);
+    for y in share.y.iter() {
+        hasher.update([y.0]);
+    }
+    hasher.finalize().into()
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let digest = Sha256::digest(bytes);
+    let mut checksum = [0; CHECKSUM_LENGTH];
+    checksum.copy_from_slice(&digest[..CHECKSUM_LENGTH]);
+    checksum
+}
+
+/// A `Share` wrapped with the metadata needed to audit it without recovering the secret: a format version,
+/// the random ID of the set it was dealt in, the threshold, digests of every share of the set and a checksum.
+///
+/// Each digest is the SHA-256 hash of a share along with its set ID and a random nonce only known to its
+/// holder, so that holders can check that their share was not modified since it was dealt, while the digests
+/// reveal nothing about the other shares. The checksum, the first 4 bytes of the SHA-256 hash of the rest of the
+/// envelope, catches typos when shares are copied by hand.
+///
+/// The digests are not the commitments of a verifiable secret sharing scheme: they say nothing about whether
+/// the shares lie on a single polynomial of degree `threshold - 1`, so a dishonest dealer can hand out
+/// inconsistent shares with valid digests. `Sharks::verify_envelopes` only detects those once there are
+/// more distinct shares than the threshold.
+///
+/// Envelopes are serialized as the version, set ID, threshold, number of shares, `x`, nonce, digests,
+/// `y` bytes and checksum.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ Sharks, ShareEnvelope };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # use core::convert::TryFrom;
+/// let sharks = Sharks(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// // Deal 5 shares of secret [1, 2, 3, 4] in envelopes
+/// let envelopes = sharks.dealer_envelopes_rng(&[1, 2, 3, 4], 5, &mut rng).unwrap();
+/// assert_eq!(envelopes[0].threshold, 3);
+/// // Audit 3 of them, without recovering the secret
+/// assert!(sharks.verify_envelopes(&envelopes[2..]).is_ok());
+/// // A typo is caught by the checksum
+/// let mut bytes = Vec::from(&envelopes[0]);
+/// bytes[50] ^= 1;
+/// assert!(ShareEnvelope::try_from(bytes.as_slice()).is_err());
+/// // Recover the original secret!
+/// let shares = envelopes.iter().map(|e| &e.share);
+/// assert_eq!(sharks.recover(shares).unwrap().expose_secret(), &[1, 2, 3, 4]);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct ShareEnvelope {
+    pub set_id: [u8; SET_ID_LENGTH],
+    pub threshold: u8,
+    pub nonce: [u8; NONCE_LENGTH],
+    pub digests: Vec<[u8; DIGEST_LENGTH]>,
+    pub share: Share,
+}
+
+impl ShareEnvelope {
+    /// Version of the serialization format.
+    pub const VERSION: u8 = 1;
+
+    /// Checks that the share matches the digest the dealer made of it, returning an `Err` otherwise.
+    pub fn verify_digest(&self) -> Result<(), &'static str> {
+        let x = self.share.x.0 as usize;
+        match self.digests.get(x.wrapping_sub(1)) {
+            Some(c) if *c == digest(&self.set_id, &self.nonce, &self.share) => Ok(()),
+            _ => Err("Share does not match its digest"),
+        }
+    }
+}
+
+impl Sharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer_envelopes` method.
+    ///
+    /// Given a `secret` byte slice, returns `count` new shares at `x = 1, 2, ..` wrapped in envelopes
+    /// of a new set, each holding the digests of all of them.
+    /// If the threshold is zero or greater than `count`, or the secret is empty, an `Err` is returned.
+    /// A random number generator has to be provided.
+    pub fn dealer_envelopes_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        count: u8,
+        rng: &mut R,
+    ) -> Result<Vec<ShareEnvelope>, &'static str> {
+        if self.0 == 0 || count < self.0 {
+            return Err(
+                "The number of shares must be at least the threshold, which can not be zero",
+            );
+        }
+        if secret.is_empty() {
+            return Err("Secret can not be empty");
+        }
+
+        let set_id: [u8; SET_ID_LENGTH] = rng.gen();
+        let mut envelopes: Vec<ShareEnvelope> = self
+            .dealer_rng(secret, rng)
+            .take(count as usize)
+            .map(|share| ShareEnvelope {
+                set_id,
+                threshold: self.0,
+                nonce: [0; NONCE_LENGTH],
+                digests: Vec::new(),
+                share,
+            })
+            .collect();
+
+        for envelope in envelopes.iter_mut() {
+            rng.fill(&mut envelope.nonce);
+        }
+        let digests: Vec<[u8; DIGEST_LENGTH]> = envelopes
+            .iter()
+            .map(|e| digest(&e.set_id, &e.nonce, &e.share))
+            .collect();
+        for envelope in envelopes.iter_mut() {
+            envelope.digests = digests.clone();
+        }
+
+        Ok(envelopes)
+    }
+
+    /// Given a `secret` byte slice, returns `count` new shares wrapped in envelopes.
+    /// If the threshold is zero or greater than `count`, or the secret is empty, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Sharks, ShareEnvelope };
+    /// # let sharks = Sharks(3);
+    /// // Obtain 5 shares for secret [1, 2]
+    /// let envelopes: Vec<ShareEnvelope> = sharks.dealer_envelopes(&[1, 2], 5).unwrap();
+    /// assert!(envelopes.iter().all(|e| e.set_id == envelopes[0].set_id));
+    #[cfg(feature = "std")]
+    pub fn dealer_envelopes(
+        &self,
+        secret: &[u8],
+        count: u8,
+    ) -> Result<Vec<ShareEnvelope>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_envelopes_rng(secret, count, &mut rng)
+    }
+
+    /// Given an iterable collection of share envelopes, checks that they can recover a secret, without recovering it.
+    /// All envelopes must belong to the same set, have been dealt with this threshold and hold the shares
+    /// their digests were made of. The shares are then checked with `verify`, which can only tell whether they
+    /// lie on the same polynomials when there are more distinct shares than the threshold.
+    /// Returns an `Err` describing the first failed check.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Sharks, ShareEnvelope };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = Sharks(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut envelopes = sharks.dealer_envelopes_rng(&[1, 2], 4, &mut rng).unwrap();
+    /// assert!(sharks.verify_envelopes(&envelopes).is_ok());
+    /// // Replace the last share
+    /// envelopes[3].share = &envelopes[3].share * 2;
+    /// assert!(sharks.verify_envelopes(&envelopes).is_err());
+    pub fn verify_envelopes<'a, T>(&self, envelopes: T) -> Result<(), &'static str>
+    where
+        T: IntoIterator<Item = &'a ShareEnvelope>,
+        T::IntoIter: Iterator<Item = &'a ShareEnvelope>,
+    {
+        let envelopes: Vec<&ShareEnvelope> = envelopes.into_iter().collect();
+
+        if let Some(first) = envelopes.first() {
+            for envelope in envelopes.iter() {
+                if envelope.set_id != first.set_id
+                    || envelope.threshold != first.threshold
+                    || envelope.digests != first.digests
+                {
+                    return Err("Shares belong to different sets");
+                }
+                envelope.verify_digest()?;
+            }
+            if first.threshold != self.0 {
+                return Err("Shares were dealt with a different threshold");
+            }
+        }
+
+        self.verify(envelopes.iter().map(|e| &e.share))
+            .map_err(Into::into)
+    }
+}
+
+/// Obtains a byte vector from a `ShareEnvelope` instance
+impl From<&ShareEnvelope> for Vec<u8> {
+    fn from(e: &ShareEnvelope) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_LENGTH + e.digests.len() * DIGEST_LENGTH + e.share.y.len() + CHECKSUM_LENGTH,
+        );
+        bytes.push(ShareEnvelope::VERSION);
+        bytes.extend_from_slice(&e.set_id);
+        bytes.push(e.threshold);
+        bytes.push(e.digests.len() as u8);
+        bytes.push(e.share.x.0);
+        bytes.extend_from_slice(&e.nonce);
+        for c in e.digests.iter() {
+            bytes.extend_from_slice(c);
+        }
+        bytes.extend(e.share.y.iter().map(|p| p.0));
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        bytes
+    }
+}
+
+/// Obtains a `ShareEnvelope` instance from a byte slice, checking its version and checksum.
+/// The digests are not checked, see `ShareEnvelope::verify_digest`.
+impl TryFrom<&[u8]> for ShareEnvelope {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<ShareEnvelope, Self::Error> {
+        if s.len() < HEADER_LENGTH + CHECKSUM_LENGTH {
+            return Err("Share envelope is too short");
+        }
+        if s[0] != ShareEnvelope::VERSION {
+            return Err("Unsupported share envelope version");
+        }
+        let (body, check) = s.split_at(s.len() - CHECKSUM_LENGTH);
+        if checksum(body) != check {
+            return Err("Share envelope checksum does not match, it may have a typo");
+        }
+
+        let (threshold, count, x) = (body[17], body[18] as usize, body[19]);
+        if threshold == 0 || count < threshold as usize {
+            return Err(
+                "The number of shares must be at least the threshold, which can not be zero",
+            );
+        }
+        if x == 0 || x as usize > count {
+            return Err("Share x is out of range");
+        }
+        let y = &body[HEADER_LENGTH..];
+        if y.len() <= count * DIGEST_LENGTH {
+            return Err("Share envelope is too short");
+        }
+
+        let mut set_id = [0; SET_ID_LENGTH];
+        set_id.copy_from_slice(&body[1..17]);
+        let mut nonce = [0; NONCE_LENGTH];
+        nonce.copy_from_slice(&body[20..HEADER_LENGTH]);
+        let (digests, y) = y.split_at(count * DIGEST_LENGTH);
+
+        Ok(ShareEnvelope {
+            set_id,
+            threshold,
+            nonce,
+            digests: digests
+                .chunks(DIGEST_LENGTH)
+                .map(|c| {
+                    let mut digest = [0; DIGEST_LENGTH];
+                    digest.copy_from_slice(c);
+                    digest
+                })
+                .collect(),
+            share: Share {
+                x: GF256(x),
+                y: y.iter().map(|p| GF256(*p)).collect(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShareEnvelope, Sharks};
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn make_envelopes(sharks: &Sharks, secret: &[u8], count: u8) -> Vec<ShareEnvelope> {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        sharks
+            .dealer_envelopes_rng(secret, count, &mut rng)
+            .unwrap()
+    }
+
+    #[test]
+    fn dealer_envelopes_works() {
+        let envelopes = make_envelopes(&Sharks(3), &[1, 2, 3], 5);
+        assert_eq!(envelopes.len(), 5);
+        for (i, envelope) in envelopes.iter().enumerate() {
+            assert_eq!(envelope.share.x.0 as usize, i + 1);
+            assert_eq!(envelope.threshold, 3);
+            assert_eq!(envelope.digests.len(), 5);
+            assert!(envelope.verify_digest().is_ok());
+        }
+        assert_ne!(envelopes[0].nonce, envelopes[1].nonce);
+
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        assert!(Sharks(0).dealer_envelopes_rng(&[1], 2, &mut rng).is_err());
+        assert!(Sharks(3).dealer_envelopes_rng(&[1], 2, &mut rng).is_err());
+        assert!(Sharks(2).dealer_envelopes_rng(&[], 2, &mut rng).is_err());
+    }
+
+    #[test]
+    fn serialization_works() {
+        let envelopes = make_envelopes(&Sharks(2), &[1, 2, 3], 3);
+        let bytes = Vec::from(&envelopes[1]);
+        assert_eq!(bytes.len(), 36 + 3 * 32 + 3 + 4);
+        assert_eq!(bytes[0], ShareEnvelope::VERSION);
+
+        let envelope = ShareEnvelope::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(envelope.set_id, envelopes[1].set_id);
+        assert_eq!(envelope.threshold, 2);
+        assert_eq!(envelope.nonce, envelopes[1].nonce);
+        assert_eq!(envelope.digests, envelopes[1].digests);
+        assert_eq!(Vec::from(&envelope.share), Vec::from(&envelopes[1].share));
+    }
+
+    #[test]
+    fn test_invalid_envelopes_err() {
+        let envelopes = make_envelopes(&Sharks(2), &[1, 2, 3], 3);
+        let bytes = Vec::from(&envelopes[0]);
+
+        // Every single byte typo is caught by the checksum
+        for i in 0..bytes.len() {
+            let mut typo = bytes.clone();
+            typo[i] ^= 0x10;
+            assert!(ShareEnvelope::try_from(typo.as_slice()).is_err());
+        }
+        assert!(ShareEnvelope::try_from(&bytes[..bytes.len() - 1]).is_err());
+        assert!(ShareEnvelope::try_from(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn verify_envelopes_works() {
+        let sharks = Sharks(3);
+        let envelopes = make_envelopes(&sharks, &[1, 2, 3], 5);
+        assert!(sharks.verify_envelopes(&envelopes).is_ok());
+        assert!(sharks.verify_envelopes(&envelopes[..3]).is_ok());
+        assert!(sharks.verify_envelopes(&envelopes[..2]).is_err());
+        assert!(Sharks(2).verify_envelopes(&envelopes).is_err());
+
+        // Shares of another set
+        let mut rng = ChaCha8Rng::from_seed([0x91; 32]);
+        let others = sharks
+            .dealer_envelopes_rng(&[1, 2, 3], 5, &mut rng)
+            .unwrap();
+        let mixed = [&envelopes[0], &envelopes[1], &others[2]];
+        assert_eq!(
+            sharks.verify_envelopes(mixed.iter().copied()),
+            Err("Shares belong to different sets")
+        );
+
+        // A share replaced by another one at the same `x`
+        let mut replaced = envelopes.clone();
+        replaced[4].share = &replaced[4].share * 2;
+        assert_eq!(
+            sharks.verify_envelopes(&replaced),
+            Err("Share does not match its digest")
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use core::fmt;
+
+/// Why `Sharks::recover`, `Sharks::verify` or `AdditiveSharks::recover` failed, so that callers can tell the failures apart.
+/// It converts into the `&'static str` errors of the rest of the crate, with the same messages.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ Error, Sharks, Share };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # let sharks = Sharks(3);
+/// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let shares: Vec<Share> = sharks.dealer_rng(&[1, 2], &mut rng).take(2).collect();
+/// let error = sharks.recover(&shares).unwrap_err();
+/// assert_eq!(error, Error::NotEnoughShares);
+/// assert_eq!(<&str>::from(error), "Not enough shares to recover original secret");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// There were less distinct shares than the threshold.
+    NotEnoughShares,
+    /// There were more distinct shares than an n-of-n scheme such as `AdditiveSharks` deals.
+    TooManyShares,
+    /// The shares had different lengths.
+    InconsistentShares,
+    /// Two shares had the same `x` but different `y`.
+    ConflictingShares,
+    /// The shares beyond the threshold did not lie on the same polynomials as the first ones.
+    NotOnPolynomial,
+}
+
+impl From<Error> for &'static str {
+    fn from(error: Error) -> &'static str {
+        match error {
+            Error::NotEnoughShares => "Not enough shares to recover original secret",
+            Error::TooManyShares => "Too many shares to recover original secret",
+            Error::InconsistentShares => "All shares must have the same length",
+            Error::ConflictingShares => "Shares with the same x must be equal",
+            Error::NotOnPolynomial => "Shares do not lie on the same polynomial",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str((*self).into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,275 @@
+//! C ABI, enabled with the `ffi` feature, for embedding the crate in non-Rust programs.
+//!
+//! Shares are handed out as opaque `SharksShare` handles, and byte buffers as pointer and length pairs,
+//! all of them allocated by the crate. They must be released with `sharks_share_free` and `sharks_bytes_free`
+//! respectively, which wipe their contents. Every function returns a `SharksError` code, `SHARKS_ERROR_OK` on success,
+//! and only writes its outputs on success.
+//!
+//! The `include/sharks.h` header declares this API. It is generated from this module by cbindgen,
+//! with `cbindgen --config cbindgen.toml --output include/sharks.h`, and the test suite checks it is up to date.
+//! Link against the library built with `cargo rustc --release --features ffi --crate-type staticlib`
+//! (or `cdylib`).
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::{ptr, slice};
+
+use zeroize::Zeroize;
+
+use super::share::Share;
+use super::{Error, Sharks};
+
+/// Result codes of the C API.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SharksError {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument was out of range, such as an empty secret or more shares than the field allows.
+    InvalidArgument = 2,
+    /// There were less distinct shares than the threshold.
+    NotEnoughShares = 3,
+    /// The shares were inconsistent with each other, such as having different lengths.
+    InconsistentShares = 4,
+    /// The bytes could not be deserialized into a share.
+    InvalidShare = 5,
+}
+
+impl From<Error> for SharksError {
+    fn from(error: Error) -> SharksError {
+        match error {
+            Error::NotEnoughShares => SharksError::NotEnoughShares,
+            Error::TooManyShares
+            | Error::InconsistentShares
+            | Error::ConflictingShares
+            | Error::NotOnPolynomial => SharksError::InconsistentShares,
+        }
+    }
+}
+
+/// Opaque handle to a share.
+pub struct SharksShare(Share);
+
+// Hands a byte vector over to the caller, to be released with `sharks_bytes_free`
+fn export_bytes(bytes: Vec<u8>, bytes_out: *mut *mut u8, len_out: *mut usize) {
+    let bytes = bytes.into_boxed_slice();
+    unsafe {
+        *len_out = bytes.len();
+        *bytes_out = Box::into_raw(bytes) as *mut u8;
+    }
+}
+
+/// Splits the `secret_len` bytes at `secret` into `count` shares, any `threshold` of which recover the secret,
+/// and writes their handles to the `shares_out` array, which must have room for `count` handles.
+///
+/// # Safety
+///
+/// `secret` must point to `secret_len` readable bytes, and `shares_out` to `count` writable handles.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_split(
+    threshold: u8,
+    secret: *const u8,
+    secret_len: usize,
+    count: usize,
+    shares_out: *mut *mut SharksShare,
+) -> SharksError {
+    if secret.is_null() || shares_out.is_null() {
+        return SharksError::NullPointer;
+    }
+    if threshold == 0 || secret_len == 0 || count < threshold as usize || count > 255 {
+        return SharksError::InvalidArgument;
+    }
+
+    let secret = slice::from_raw_parts(secret, secret_len);
+    for (i, share) in Sharks(threshold).dealer(secret).take(count).enumerate() {
+        *shares_out.add(i) = Box::into_raw(Box::new(SharksShare(share)));
+    }
+    SharksError::Ok
+}
+
+/// Recovers the secret from the `count` handles of the `shares` array, with the given minimum `threshold`,
+/// which can not be zero.
+/// On success, writes a buffer with the secret to `secret_out` and its length to `secret_len_out`,
+/// to be released with `sharks_bytes_free`.
+///
+/// # Safety
+///
+/// `shares` must point to `count` valid handles, and `secret_out` and `secret_len_out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_combine(
+    threshold: u8,
+    shares: *const *const SharksShare,
+    count: usize,
+    secret_out: *mut *mut u8,
+    secret_len_out: *mut usize,
+) -> SharksError {
+    if shares.is_null() || secret_out.is_null() || secret_len_out.is_null() {
+        return SharksError::NullPointer;
+    }
+    // Any share would be taken for the secret
+    if threshold == 0 {
+        return SharksError::InvalidArgument;
+    }
+
+    let handles = slice::from_raw_parts(shares, count);
+    if handles.iter().any(|h| h.is_null()) {
+        return SharksError::NullPointer;
+    }
+    match Sharks(threshold).recover(handles.iter().map(|h| &(**h).0)) {
+        Ok(secret) => {
+            export_bytes(secret.expose_secret().to_vec(), secret_out, secret_len_out);
+            SharksError::Ok
+        }
+        Err(error) => error.into(),
+    }
+}
+
+/// Serializes a share as its `x` byte followed by its `y` bytes. On success, writes the buffer to `bytes_out`
+/// and its length to `len_out`, to be released with `sharks_bytes_free`.
+///
+/// # Safety
+///
+/// `share` must be a valid handle, and `bytes_out` and `len_out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_share_to_bytes(
+    share: *const SharksShare,
+    bytes_out: *mut *mut u8,
+    len_out: *mut usize,
+) -> SharksError {
+    if share.is_null() || bytes_out.is_null() || len_out.is_null() {
+        return SharksError::NullPointer;
+    }
+
+    export_bytes(Vec::from(&(*share).0), bytes_out, len_out);
+    SharksError::Ok
+}
+
+/// Deserializes a share from the `len` bytes at `bytes`, as written by `sharks_share_to_bytes`.
+/// On success, writes its handle to `share_out`, to be released with `sharks_share_free`.
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes, and `share_out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_share_from_bytes(
+    bytes: *const u8,
+    len: usize,
+    share_out: *mut *mut SharksShare,
+) -> SharksError {
+    if bytes.is_null() || share_out.is_null() {
+        return SharksError::NullPointer;
+    }
+
+    match Share::try_from(slice::from_raw_parts(bytes, len)) {
+        Ok(share) => {
+            *share_out = Box::into_raw(Box::new(SharksShare(share)));
+            SharksError::Ok
+        }
+        Err(_) => SharksError::InvalidShare,
+    }
+}
+
+/// Wipes and releases a share handle. Null handles are ignored.
+///
+/// # Safety
+///
+/// `share` must be null or a handle returned by this API, not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_share_free(share: *mut SharksShare) {
+    if !share.is_null() {
+        drop(Box::from_raw(share));
+    }
+}
+
+/// Wipes and releases a buffer returned by this API. Null buffers are ignored.
+///
+/// # Safety
+///
+/// `bytes` must be null or a buffer returned by this API along with its `len`, not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn sharks_bytes_free(bytes: *mut u8, len: usize) {
+    if !bytes.is_null() {
+        let mut bytes = Box::from_raw(ptr::slice_from_raw_parts_mut(bytes, len));
+        bytes.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ptr;
+
+    #[test]
+    fn split_and_combine_work() {
+        let mut shares = [ptr::null_mut(); 5];
+        let result = unsafe { sharks_split(3, [1, 2, 3].as_ptr(), 3, 5, shares.as_mut_ptr()) };
+        assert_eq!(result, SharksError::Ok);
+
+        let (mut secret, mut len) = (ptr::null_mut(), 0);
+        let handles: Vec<*const SharksShare> = shares[2..].iter().map(|s| *s as *const _).collect();
+        let result = unsafe { sharks_combine(3, handles.as_ptr(), 3, &mut secret, &mut len) };
+        assert_eq!(result, SharksError::Ok);
+        assert_eq!(unsafe { slice::from_raw_parts(secret, len) }, [1, 2, 3]);
+
+        let result = unsafe { sharks_combine(3, handles.as_ptr(), 2, &mut secret, &mut len) };
+        assert_eq!(result, SharksError::NotEnoughShares);
+        let result = unsafe { sharks_combine(0, handles.as_ptr(), 1, &mut secret, &mut len) };
+        assert_eq!(result, SharksError::InvalidArgument);
+
+        // Recovery failures are told apart
+        let mut short = ptr::null_mut();
+        unsafe { sharks_share_from_bytes([6, 1].as_ptr(), 2, &mut short) };
+        let mixed = [handles[0], handles[1], short as *const _];
+        let result = unsafe { sharks_combine(3, mixed.as_ptr(), 3, &mut secret, &mut len) };
+        assert_eq!(result, SharksError::InconsistentShares);
+
+        unsafe {
+            sharks_bytes_free(secret, len);
+            sharks_share_free(short);
+            shares.iter().for_each(|s| sharks_share_free(*s));
+        }
+    }
+
+    #[test]
+    fn serialization_works() {
+        let (mut share, mut bytes, mut len) = (ptr::null_mut(), ptr::null_mut(), 0);
+        let result = unsafe { sharks_share_from_bytes([1, 2, 3].as_ptr(), 3, &mut share) };
+        assert_eq!(result, SharksError::Ok);
+        let result = unsafe { sharks_share_to_bytes(share, &mut bytes, &mut len) };
+        assert_eq!(result, SharksError::Ok);
+        assert_eq!(unsafe { slice::from_raw_parts(bytes, len) }, [1, 2, 3]);
+
+        let result = unsafe { sharks_share_from_bytes([1].as_ptr(), 1, &mut share) };
+        assert_eq!(result, SharksError::InvalidShare);
+
+        unsafe {
+            sharks_bytes_free(bytes, len);
+            sharks_share_free(share);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_err() {
+        let mut shares = [ptr::null_mut(); 2];
+        let split = |threshold, len, count, out| unsafe {
+            sharks_split(threshold, [1, 2].as_ptr(), len, count, out)
+        };
+        assert_eq!(
+            split(3, 2, 2, shares.as_mut_ptr()),
+            SharksError::InvalidArgument
+        );
+        assert_eq!(
+            split(0, 2, 2, shares.as_mut_ptr()),
+            SharksError::InvalidArgument
+        );
+        assert_eq!(
+            split(2, 0, 2, shares.as_mut_ptr()),
+            SharksError::InvalidArgument
+        );
+        assert_eq!(split(2, 2, 2, ptr::null_mut()), SharksError::NullPointer);
+        assert!(shares.iter().all(|s| s.is_null()));
+    }
+}
@@ -0,0 +1,325 @@
+//! Heap-free API for targets without an allocator, such as secure elements, with shares of a fixed
+//! secret size `N` held in arrays. It only depends on `core`, so it is available without the `alloc` feature:
+//!
+//! ```toml
+//! sharks = { version = "0.5", default-features = false }
+//! ```
+//!
+//! Shares are written into caller provided buffers, and the secret is recovered into a caller provided array.
+//! Given the same random number generator, `Sharks::dealer_into` generates the same shares as `Sharks::dealer_rng`,
+//! and both share types serialize to the same bytes.
+//!
+//! Usage example:
+//! ```
+//! use sharks::{fixed::Share, Sharks};
+//! use rand_chacha::rand_core::SeedableRng;
+//!
+//! // Set a minimum threshold of 3 shares
+//! let sharks = Sharks(3);
+//! // Generate 5 shares for secret [1, 2, 3, 4]
+//! let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+//! let mut shares: [Share<4>; 5] = Default::default();
+//! sharks.dealer_into(&[1, 2, 3, 4], &mut rng, &mut shares).unwrap();
+//! // Recover the original secret!
+//! let mut secret = [0; 4];
+//! sharks.recover_into(&shares[1..4], &mut secret).unwrap();
+//! assert_eq!(secret, [1, 2, 3, 4]);
+//! ```
+
+use core::convert::TryFrom;
+
+use rand::distributions::{Distribution, Uniform};
+
+use super::field::GF256;
+use super::Sharks;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+const ZERO: GF256 = GF256(0);
+
+/// A share of a secret of `N` bytes, which is serialized as its `x` byte followed by its `y` bytes.
+///
+/// Usage example:
+/// ```
+/// use sharks::fixed::Share;
+/// use core::convert::TryFrom;
+///
+/// let share = Share::<2>::try_from(&[1, 2, 3][..]).unwrap();
+/// let mut bytes = [0; 3];
+/// share.write_bytes(&mut bytes).unwrap();
+/// assert_eq!(bytes, [1, 2, 3]);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct Share<const N: usize> {
+    pub x: GF256,
+    pub y: [GF256; N],
+}
+
+impl<const N: usize> Default for Share<N> {
+    fn default() -> Self {
+        Share {
+            x: ZERO,
+            y: [ZERO; N],
+        }
+    }
+}
+
+impl<const N: usize> Share<N> {
+    /// Writes the `N + 1` bytes of the share at the start of `bytes`.
+    /// If `bytes` is too short an `Err` is returned.
+    pub fn write_bytes(&self, bytes: &mut [u8]) -> Result<(), &'static str> {
+        if bytes.len() < N + 1 {
+            return Err("Buffer is too short for the share");
+        }
+
+        bytes[0] = self.x.0;
+        for (b, y) in bytes[1..].iter_mut().zip(self.y.iter()) {
+            *b = y.0;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for Share<N> {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<Share<N>, Self::Error> {
+        if s.len() != N + 1 {
+            return Err("A Share must be exactly one byte longer than the secret");
+        }
+
+        let mut share = Share {
+            x: GF256(s[0]),
+            y: [ZERO; N],
+        };
+        for (y, b) in share.y.iter_mut().zip(s[1..].iter()) {
+            *y = GF256(*b);
+        }
+        Ok(share)
+    }
+}
+
+impl Sharks {
+    /// Heap-free counterpart of `dealer_rng`, which writes new shares of `secret` into all of `shares`,
+    /// at `x = 1, 2, ..`. If `shares` holds more than 255 shares an `Err` is returned.
+    ///
+    /// Polynomial coefficients are kept on the stack, one secret byte at a time,
+    /// and wiped before returning with the `zeroize_memory` feature.
+    pub fn dealer_into<R: rand::Rng, const N: usize>(
+        &self,
+        secret: &[u8; N],
+        rng: &mut R,
+        shares: &mut [Share<N>],
+    ) -> Result<(), &'static str> {
+        if shares.len() > u8::MAX as usize {
+            return Err("At most 255 shares can be generated");
+        }
+
+        for (x, share) in (1..=u8::MAX).zip(shares.iter_mut()) {
+            share.x = GF256(x);
+        }
+
+        // Random coefficients of the polynomial, from the highest degree down to the linear term
+        let mut poly = [ZERO; u8::MAX as usize - 1];
+        let degree = (self.0 as usize).saturating_sub(1);
+        let between = Uniform::new_inclusive(1, 255);
+
+        for (i, s) in secret.iter().enumerate() {
+            for c in poly[..degree].iter_mut() {
+                *c = GF256(between.sample(rng));
+            }
+            for share in shares.iter_mut() {
+                let x = share.x.clone();
+                share.y[i] = poly[..degree]
+                    .iter()
+                    .fold(GF256(0), |acc, c| acc * x.clone() + c.clone())
+                    * x
+                    + GF256(*s);
+            }
+        }
+
+        // The coefficients would give the secret away along with the shares
+        #[cfg(feature = "zeroize_memory")]
+        poly.zeroize();
+        Ok(())
+    }
+
+    /// Heap-free counterpart of `recover`, which writes the original secret into `secret`.
+    /// If the number of distinct shares is less than the minimum threshold, or shares at the same `x`
+    /// have different `y`, an `Err` is returned, and `secret` is left untouched.
+    ///
+    /// Duplicate shares are detected with fixed size tables on the stack, whatever the number of shares.
+    /// Exact duplicates are skipped.
+    /// Only the first `threshold` distinct shares are interpolated, or all of them if the threshold is zero.
+    pub fn recover_into<const N: usize>(
+        &self,
+        shares: &[Share<N>],
+        secret: &mut [u8; N],
+    ) -> Result<(), &'static str> {
+        let limit = if self.0 == 0 { 256 } else { self.0 as usize };
+        // Bit set of the `x` already seen, and the distinct `x` in order of appearance
+        let mut seen = [0u8; 32];
+        let mut xs = [ZERO; 256];
+        let mut count = 0;
+
+        for share in shares {
+            let x = share.x.0 as usize;
+            if seen[x / 8] & (1 << (x % 8)) != 0 {
+                // The first share at this `x` comes before, and duplicates must match it
+                if shares.iter().any(|s| s.x == share.x && s.y != share.y) {
+                    return Err("Shares with the same x must be equal");
+                }
+            } else {
+                seen[x / 8] |= 1 << (x % 8);
+                if count < limit {
+                    xs[count] = share.x.clone();
+                    count += 1;
+                }
+            }
+        }
+
+        if count == 0 || count < self.0 as usize {
+            return Err("Not enough shares to recover original secret");
+        }
+
+        secret.iter_mut().for_each(|b| *b = 0);
+        for x_i in xs[..count].iter() {
+            let l_i: GF256 = xs[..count]
+                .iter()
+                .filter(|x_j| *x_j != x_i)
+                .map(|x_j| x_j.clone() / (x_i.clone() - x_j.clone()))
+                .product();
+            // There is always one, as `x_i` was taken from the shares
+            if let Some(share) = shares.iter().find(|s| s.x == *x_i) {
+                for (b, y) in secret.iter_mut().zip(share.y.iter()) {
+                    *b ^= (l_i.clone() * y.clone()).0;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Share, Sharks, GF256};
+    use core::convert::TryFrom;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn make_shares<const N: usize, const M: usize>(
+        sharks: &Sharks,
+        secret: &[u8; N],
+    ) -> [Share<N>; M] {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let mut shares: [Share<N>; M] = core::array::from_fn(|_| Share::default());
+        sharks.dealer_into(secret, &mut rng, &mut shares).unwrap();
+        shares
+    }
+
+    #[test]
+    fn dealer_into_works() {
+        let shares: [Share<2>; 3] = make_shares(&Sharks(2), &[1, 2]);
+        let values: [(u8, [u8; 2]); 3] =
+            core::array::from_fn(|i| (shares[i].x.0, [shares[i].y[0].0, shares[i].y[1].0]));
+        assert_eq!(values, [(1, [49, 96]), (2, [97, 198]), (3, [81, 164])]);
+
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let mut too_many: [Share<1>; 256] = core::array::from_fn(|_| Share::default());
+        assert!(Sharks(2)
+            .dealer_into(&[1], &mut rng, &mut too_many)
+            .is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn dealer_into_matches_dealer_rng() {
+        use alloc::vec::Vec;
+
+        let sharks = Sharks(5);
+        let shares: [Share<3>; 10] = make_shares(&sharks, &[1, 2, 3]);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        for (fixed, share) in shares.iter().zip(sharks.dealer_rng(&[1, 2, 3], &mut rng)) {
+            let mut bytes = [0; 4];
+            fixed.write_bytes(&mut bytes).unwrap();
+            assert_eq!(bytes[..], Vec::from(&share)[..]);
+        }
+    }
+
+    #[test]
+    fn recover_into_works() {
+        let sharks = Sharks(3);
+        let shares: [Share<4>; 5] = make_shares(&sharks, &[1, 2, 3, 4]);
+        let mut secret = [0; 4];
+        sharks.recover_into(&shares[2..], &mut secret).unwrap();
+        assert_eq!(secret, [1, 2, 3, 4]);
+
+        let mut secret = [0; 4];
+        Sharks(0).recover_into(&shares, &mut secret).unwrap();
+        assert_eq!(secret, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn recover_into_insufficient_shares_err() {
+        let sharks = Sharks(255);
+        let shares: [Share<1>; 254] = make_shares(&sharks, &[1]);
+        let mut secret = [7];
+        assert!(sharks.recover_into(&shares, &mut secret).is_err());
+        assert_eq!(secret, [7]);
+        assert!(sharks.recover_into(&shares[..0], &mut secret).is_err());
+    }
+
+    #[test]
+    fn recover_into_duplicate_shares_err() {
+        let sharks = Sharks(3);
+        let mut shares: [Share<2>; 3] = make_shares(&sharks, &[1, 2]);
+        shares[2] = shares[0].clone();
+        let mut secret = [0; 2];
+        assert!(sharks.recover_into(&shares, &mut secret).is_err());
+
+        // Duplicates are skipped when there are enough distinct shares
+        let mut shares: [Share<2>; 4] = make_shares(&sharks, &[1, 2]);
+        shares[1] = shares[0].clone();
+        sharks.recover_into(&shares, &mut secret).unwrap();
+        assert_eq!(secret, [1, 2]);
+
+        // But shares at the same x with different y are rejected, leaving the secret untouched
+        let mut shares: [Share<2>; 5] = make_shares(&sharks, &[1, 2]);
+        shares[4] = shares[0].clone();
+        shares[4].y[1] = GF256(shares[4].y[1].0 ^ 1);
+        let mut secret = [0; 2];
+        assert_eq!(
+            sharks.recover_into(&shares, &mut secret),
+            Err("Shares with the same x must be equal")
+        );
+        assert_eq!(secret, [0, 0]);
+    }
+
+    #[test]
+    fn integration_works() {
+        let sharks = Sharks(255);
+        let shares: [Share<4>; 255] = make_shares(&sharks, &[1, 2, 3, 4]);
+        let mut secret = [0; 4];
+        sharks.recover_into(&shares, &mut secret).unwrap();
+        assert_eq!(secret, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn serialization_works() {
+        let share = Share::<2>::try_from(&[1, 2, 3][..]).unwrap();
+        assert_eq!(share.x, GF256(1));
+        assert_eq!(share.y, [GF256(2), GF256(3)]);
+
+        let mut bytes = [0; 4];
+        share.write_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [1, 2, 3, 0]);
+        assert!(share.write_bytes(&mut bytes[..2]).is_err());
+
+        assert!(Share::<2>::try_from(&[1, 2][..]).is_err());
+        assert!(Share::<2>::try_from(&[1, 2, 3, 4][..]).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+// Arithmetic over binary extension fields GF(2**n) of arbitrary degree, for interoperability with tools
+// that work over a field as large as the whole secret, such as `ssss`.
+// Elements are polynomials over GF(2), stored as little endian vectors of 64 bits limbs, and reduced modulo
+// an irreducible polynomial `x**n + x**k_1 + .. + x**k_m + 1`.
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct Element(Vec<u64>);
+
+pub struct Field {
+    degree: usize,
+    taps: Vec<usize>,
+}
+
+impl Field {
+    // Defines GF(2**degree) by its reduction polynomial, given the exponents of its terms between 0 and `degree`.
+    // The polynomial is assumed to be irreducible.
+    pub fn new(degree: usize, taps: &[usize]) -> Self {
+        Self {
+            degree,
+            taps: taps.to_vec(),
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    fn limbs(&self) -> usize {
+        self.degree.div_ceil(64)
+    }
+
+    pub fn zero(&self) -> Element {
+        Element(vec![0; self.limbs()])
+    }
+
+    pub fn element(&self, x: u64) -> Element {
+        let mut e = self.zero();
+        e.0[0] = x;
+        e
+    }
+
+    // Reads a big endian byte slice, which must fit in `degree` bits.
+    pub fn read_bytes(&self, bytes: &[u8]) -> Element {
+        let mut e = self.zero();
+        for (i, b) in bytes.iter().rev().enumerate() {
+            e.0[i / 8] |= (*b as u64) << (8 * (i % 8));
+        }
+        e
+    }
+
+    // Writes a big endian byte vector, `degree / 8` bytes long rounded up.
+    pub fn write_bytes(&self, e: &Element) -> Vec<u8> {
+        (0..self.degree.div_ceil(8))
+            .rev()
+            .map(|i| (e.0[i / 8] >> (8 * (i % 8))) as u8)
+            .collect()
+    }
+
+    pub fn add(&self, a: &Element, b: &Element) -> Element {
+        Element(a.0.iter().zip(b.0.iter()).map(|(a, b)| a ^ b).collect())
+    }
+
+    // Multiplies bit by bit from the most significant bit of `b`, reducing after every shift.
+    pub fn mul(&self, a: &Element, b: &Element) -> Element {
+        let mut result = self.zero();
+
+        for i in (0..self.degree).rev() {
+            let mut carry = 0;
+            for limb in result.0.iter_mut() {
+                let next = *limb >> 63;
+                *limb = (*limb << 1) | carry;
+                carry = next;
+            }
+
+            let top = self.degree / 64;
+            let overflow = if self.degree.is_multiple_of(64) {
+                carry
+            } else {
+                let bit = (result.0[top] >> (self.degree % 64)) & 1;
+                result.0[top] &= !(1 << (self.degree % 64));
+                bit
+            };
+            if overflow == 1 {
+                result.0[0] ^= 1;
+                for tap in &self.taps {
+                    result.0[tap / 64] ^= 1 << (tap % 64);
+                }
+            }
+
+            if (b.0[i / 64] >> (i % 64)) & 1 == 1 {
+                for (r, a) in result.0.iter_mut().zip(a.0.iter()) {
+                    *r ^= a;
+                }
+            }
+        }
+
+        result
+    }
+
+    // Computes `a**(2**degree - 2)`, which is the inverse of `a` by Fermat's little theorem.
+    pub fn inv(&self, a: &Element) -> Element {
+        let mut result = self.element(1);
+        let mut square = a.clone();
+
+        for _ in 1..self.degree {
+            square = self.mul(&square, &square);
+            result = self.mul(&result, &square);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Field;
+    use alloc::vec;
+
+    #[test]
+    fn bytes_roundtrip_works() {
+        let field = Field::new(72, &[10, 9, 3]);
+        let bytes = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(field.write_bytes(&field.read_bytes(&bytes)), bytes);
+        assert_eq!(
+            field.write_bytes(&field.element(2)),
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 2]
+        );
+    }
+
+    #[test]
+    fn mul_works() {
+        // The AES field
+        let field = Field::new(8, &[4, 3, 1]);
+        let product = field.mul(&field.element(0x53), &field.element(0xca));
+        assert_eq!(product, field.element(1));
+        assert_eq!(
+            field.mul(&field.element(3), &field.element(7)),
+            field.element(9)
+        );
+    }
+
+    #[test]
+    fn inv_works() {
+        for (degree, taps) in [(8, [4, 3, 1]), (64, [4, 3, 1]), (128, [7, 2, 1])].iter() {
+            let field = Field::new(*degree, taps);
+            let a = field.read_bytes(&[0x90; 16][..degree / 8]);
+            assert_eq!(field.mul(&a, &field.inv(&a)), field.element(1));
+        }
+    }
+}
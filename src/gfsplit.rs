@@ -0,0 +1,214 @@
+//! Interoperability with the file shares of [libgfshare](https://git.gitano.org.uk/libgfshare.git/)'s
+//! `gfsplit` and `gfcombine` tools.
+//!
+//! `libgfshare` shares secrets byte by byte over the same Galois Field as `Sharks`, with reduction polynomial
+//! `0x11d`, so its shares are plain `Share` instances. Only the on-disk layout differs: each share is stored
+//! in its own file named `<name>.NNN`, where `NNN` is the `x` coordinate as 3 decimal digits, and the file
+//! contents are the bare `y` bytes.
+//!
+//! Usage example:
+//! ```
+//! # use sharks::Sharks;
+//! # use sharks::gfsplit;
+//! # let dir = std::env::temp_dir().join(format!("sharks-gfsplit-doc-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir).unwrap();
+//! let sharks = Sharks(3);
+//! // Write 5 shares as `secret.001` to `secret.005`, which `gfcombine` can read
+//! let paths = gfsplit::split(&sharks, b"attack at dawn", dir.join("secret"), 5).unwrap();
+//! // Recover the secret from any 3 of them, or from files written by `gfsplit`
+//! let secret = gfsplit::combine(&sharks, &paths[2..]).unwrap();
+//! assert_eq!(secret.expose_secret(), b"attack at dawn");
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use super::field::GF256;
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Returns the path of the share at `x` for output `name`, as `gfsplit` names it: `<name>.NNN`.
+pub fn share_path<P: AsRef<Path>>(name: P, x: u8) -> PathBuf {
+    let mut path = OsString::from(name.as_ref());
+    path.push(format!(".{:03}", x));
+    PathBuf::from(path)
+}
+
+/// Writes `share` to its `<name>.NNN` file, returning the path of the file.
+/// On Unix the file is only readable and writable by its owner. If it already exists an `Err` of kind
+/// `AlreadyExists` is returned, so that no other share is overwritten.
+pub fn write_share<P: AsRef<Path>>(name: P, share: &Share) -> io::Result<PathBuf> {
+    let path = share_path(name, share.x.0);
+    let bytes = Secret::from(share.y.iter().map(|p| p.0).collect::<Vec<u8>>());
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&path)?.write_all(bytes.expose_secret())?;
+    Ok(path)
+}
+
+/// Reads a share from a `<name>.NNN` file, taking its `x` coordinate from the file name.
+/// If the name does not end in 3 decimal digits between 1 and 255, or the file is empty,
+/// an `Err` of kind `InvalidData` is returned.
+pub fn read_share<P: AsRef<Path>>(path: P) -> io::Result<Share> {
+    let x = path
+        .as_ref()
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| e.len() == 3 && e.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|e| e.parse::<u8>().ok())
+        .filter(|x| *x > 0)
+        .ok_or_else(|| invalid_data("Share file name must end in .001 to .255"))?;
+
+    let bytes = Secret::from(fs::read(path)?);
+    if bytes.expose_secret().is_empty() {
+        return Err(invalid_data("Share file is empty"));
+    }
+
+    Ok(Share {
+        x: GF256(x),
+        y: bytes.expose_secret().iter().map(|p| GF256(*p)).collect(),
+    })
+}
+
+/// Splits `secret` into `count` shares, written to the `<name>.001` to `<name>.NNN` files
+/// that `gfcombine` reads. Returns the paths of the files.
+pub fn split<P: AsRef<Path>>(
+    sharks: &Sharks,
+    secret: &[u8],
+    name: P,
+    count: u8,
+) -> io::Result<Vec<PathBuf>> {
+    sharks
+        .dealer(secret)
+        .take(count as usize)
+        .map(|share| write_share(name.as_ref(), &share))
+        .collect()
+}
+
+/// Recovers the secret from the share files at `paths`, such as the ones written by `gfsplit`.
+/// If a file can not be read an `Err` is returned, and if there are not enough shares or their lengths
+/// differ an `Err` of kind `InvalidData`.
+pub fn combine<P: AsRef<Path>>(sharks: &Sharks, paths: &[P]) -> io::Result<Secret> {
+    let shares = paths
+        .iter()
+        .map(read_share)
+        .collect::<io::Result<Vec<Share>>>()?;
+    sharks
+        .recover(&shares)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combine, read_share, share_path, split, write_share, GF256};
+    use crate::{Share, Sharks};
+    use std::path::PathBuf;
+    use std::{fs, process};
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sharks-gfsplit-{}-{}", test, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Shares of "gfsplit" with threshold 3, as written by `gfsplit -n 3` for x = 46, 145 and 196
+    const SHARES: [(u8, [u8; 7]); 3] = [
+        (46, [0xe4, 0x29, 0xfa, 0x96, 0x5b, 0x0a, 0xb0]),
+        (145, [0xcf, 0x40, 0xe4, 0xfe, 0xaa, 0x32, 0x58]),
+        (196, [0xaa, 0xef, 0x0b, 0xf0, 0x91, 0x90, 0xa1]),
+    ];
+
+    #[test]
+    fn share_path_works() {
+        assert_eq!(
+            share_path("keys/secret.bin", 7),
+            PathBuf::from("keys/secret.bin.007")
+        );
+        assert_eq!(share_path("secret", 255), PathBuf::from("secret.255"));
+    }
+
+    #[test]
+    fn combine_gfsplit_files_works() {
+        let dir = temp_dir("combine");
+        let paths: Vec<PathBuf> = SHARES
+            .iter()
+            .map(|(x, y)| {
+                let path = share_path(dir.join("secret"), *x);
+                fs::write(&path, y).unwrap();
+                path
+            })
+            .collect();
+
+        let secret = combine(&Sharks(3), &paths).unwrap();
+        assert_eq!(secret.expose_secret(), b"gfsplit");
+        assert!(combine(&Sharks(3), &paths[1..]).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_share_is_byte_for_byte() {
+        let dir = temp_dir("write");
+        let (x, y) = SHARES[1];
+        let share = Share {
+            x: GF256(x),
+            y: y.iter().copied().map(GF256).collect(),
+        };
+
+        let path = write_share(dir.join("secret"), &share).unwrap();
+        assert_eq!(path, dir.join("secret.145"));
+        assert_eq!(fs::read(&path).unwrap(), y);
+        assert_eq!(Vec::from(&read_share(&path).unwrap()), Vec::from(&share));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let err = write_share(dir.join("secret"), &share).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_share_checks_name() {
+        let dir = temp_dir("read");
+        for name in [
+            "secret.000",
+            "secret.256",
+            "secret.1",
+            "secret.0x1",
+            "secret",
+        ]
+        .iter()
+        {
+            fs::write(dir.join(name), [1, 2]).unwrap();
+            assert!(read_share(dir.join(name)).is_err());
+        }
+        fs::write(dir.join("secret.001"), []).unwrap();
+        assert!(read_share(dir.join("secret.001")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let dir = temp_dir("integration");
+        let paths = split(&Sharks(2), &[1, 2, 3, 4], dir.join("secret"), 3).unwrap();
+        assert_eq!(paths[2], dir.join("secret.003"));
+        let secret = combine(&Sharks(2), &[&paths[0], &paths[2]]).unwrap();
+        assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
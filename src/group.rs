@@ -0,0 +1,342 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use super::error::Error;
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+/// Struct which implements hierarchical secret sharing with group thresholds.
+/// The secret is first shared among the `groups` with the `threshold`, and then each group share
+/// is shared among the group members with the group's own `Sharks` threshold.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ GroupSharks, GroupShare, Sharks };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // Any 2 of 3 teams, each one contributing 3 of its 5 members
+/// let sharks = GroupSharks {
+///     threshold: 2,
+///     groups: vec![Sharks(3), Sharks(3), Sharks(3)],
+/// };
+/// // Obtain one iterator per group over its member shares for secret [1, 2, 3, 4]
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let dealers = sharks.dealer_rng(&[1, 2, 3, 4], &mut rng).unwrap();
+/// // Get 5 shares for each group
+/// let teams: Vec<Vec<GroupShare>> = dealers.into_iter().map(|d| d.take(5).collect()).collect();
+/// // Gather 3 members of the first and third groups, and 2 of the second one
+/// let shares: Vec<&GroupShare> = teams[0][..3]
+///     .iter()
+///     .chain(&teams[1][..2])
+///     .chain(&teams[2][2..])
+///     .collect();
+/// assert_eq!(sharks.satisfied_groups(shares.clone()).unwrap(), vec![0, 2]);
+/// // Recover the original secret!
+/// let secret = sharks.recover(shares).unwrap();
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+/// ```
+pub struct GroupSharks {
+    pub threshold: u8,
+    pub groups: Vec<Sharks>,
+}
+
+/// A member share used to reconstruct the share of its group. Can be serialized to and from a byte array.
+#[derive(Clone)]
+pub struct GroupShare {
+    pub group: u8,
+    pub share: Share,
+}
+
+impl GroupSharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns one `Iterator` along new member shares for each group.
+    /// The maximum number of groups is 255, and the maximum number of shares that can be generated
+    /// for each group is 255 too. If there are more groups an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ GroupSharks, GroupShare, Sharks };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = GroupSharks { threshold: 1, groups: vec![Sharks(2), Sharks(3)] };
+    /// // Obtain the iterators over the member shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut dealers = sharks.dealer_rng(&[1, 2], &mut rng).unwrap();
+    /// // Get 3 shares for the second group
+    /// let shares: Vec<GroupShare> = dealers.remove(1).take(3).collect();
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<impl Iterator<Item = GroupShare>>, &'static str> {
+        if self.groups.len() > u8::MAX as usize {
+            return Err("At most 255 groups are supported");
+        }
+
+        let group_shares: Vec<Share> = Sharks(self.threshold)
+            .dealer_rng(secret, rng)
+            .take(self.groups.len())
+            .collect();
+
+        Ok(group_shares
+            .iter()
+            .zip(self.groups.iter())
+            .enumerate()
+            .map(|(group, (group_share, sharks))| {
+                let bytes = Secret::from(Vec::from(group_share));
+                sharks
+                    .dealer_rng(bytes.expose_secret(), rng)
+                    .map(move |share| GroupShare {
+                        group: group as u8,
+                        share,
+                    })
+            })
+            .collect())
+    }
+
+    /// Given a `secret` byte slice, returns one `Iterator` along new member shares for each group.
+    /// The maximum number of groups is 255, and the maximum number of shares that can be generated
+    /// for each group is 255 too. If there are more groups an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ GroupSharks, GroupShare, Sharks };
+    /// # let sharks = GroupSharks { threshold: 1, groups: vec![Sharks(2), Sharks(3)] };
+    /// // Obtain the iterators over the member shares for secret [1, 2]
+    /// let mut dealers = sharks.dealer(&[1, 2]).unwrap();
+    /// // Get 3 shares for the second group
+    /// let shares: Vec<GroupShare> = dealers.remove(1).take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(
+        &self,
+        secret: &[u8],
+    ) -> Result<Vec<impl Iterator<Item = GroupShare>>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    // Recovers the shares of the groups with enough members among `shares`. Groups without enough members
+    // are left out, but any other failure to recover a group share is returned.
+    fn recover_groups<'a, T>(&self, shares: T) -> Result<Vec<(u8, Share)>, &'static str>
+    where
+        T: IntoIterator<Item = &'a GroupShare>,
+        T::IntoIter: Iterator<Item = &'a GroupShare>,
+    {
+        let mut members: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+
+        for share in shares.into_iter() {
+            if share.group as usize >= self.groups.len() {
+                return Err("Share belongs to an unknown group");
+            }
+            members.entry(share.group).or_default().push(&share.share);
+        }
+
+        let mut group_shares = Vec::with_capacity(members.len());
+        for (group, shares) in members.into_iter() {
+            match self.groups[group as usize].recover(shares) {
+                Ok(bytes) => group_shares.push((group, Share::try_from(bytes.expose_secret())?)),
+                Err(Error::NotEnoughShares) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Ok(group_shares)
+    }
+
+    /// Given an iterable collection of member shares, returns the indexes of the groups
+    /// which gathered enough members to recover their group share. If a share belongs to an unknown group,
+    /// or the shares of a group are inconsistent, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ GroupSharks, GroupShare, Sharks };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = GroupSharks { threshold: 2, groups: vec![Sharks(2), Sharks(2)] };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut dealers = sharks.dealer_rng(&[1], &mut rng).unwrap();
+    /// // Only the second group contributed enough members
+    /// let shares: Vec<GroupShare> = dealers.remove(1).take(2).collect();
+    /// assert_eq!(sharks.satisfied_groups(&shares).unwrap(), vec![1]);
+    pub fn satisfied_groups<'a, T>(&self, shares: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a GroupShare>,
+        T::IntoIter: Iterator<Item = &'a GroupShare>,
+    {
+        Ok(self
+            .recover_groups(shares)?
+            .into_iter()
+            .map(|(group, _)| group)
+            .collect())
+    }
+
+    /// Given an iterable collection of member shares from any groups, recovers the original secret.
+    /// If the number of groups with enough members is less than the minimum threshold, or the shares of a
+    /// group are inconsistent, an `Err` is returned, otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ GroupSharks, GroupShare, Sharks };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = GroupSharks { threshold: 2, groups: vec![Sharks(2), Sharks(2)] };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<GroupShare> = sharks
+    /// #     .dealer_rng(&[1], &mut rng)
+    /// #     .unwrap()
+    /// #     .into_iter()
+    /// #     .flat_map(|d| d.take(2))
+    /// #     .collect();
+    /// // Recover original secret from member shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove a member share for demonstration purposes
+    /// shares.pop();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough groups to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a GroupShare>,
+        T::IntoIter: Iterator<Item = &'a GroupShare>,
+    {
+        let group_shares = self.recover_groups(shares)?;
+        Sharks(self.threshold)
+            .recover(group_shares.iter().map(|(_, share)| share))
+            .map_err(|error| match error {
+                Error::NotEnoughShares => "Not enough groups to recover original secret",
+                error => error.into(),
+            })
+    }
+}
+
+/// Obtains a byte vector from a `GroupShare` instance
+impl From<&GroupShare> for Vec<u8> {
+    fn from(s: &GroupShare) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(s.share.y.len() + 2);
+        bytes.push(s.group);
+        bytes.extend(Vec::from(&s.share));
+        bytes
+    }
+}
+
+/// Obtains a `GroupShare` instance from a byte slice
+impl TryFrom<&[u8]> for GroupShare {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<GroupShare, Self::Error> {
+        if s.len() < 3 {
+            Err("A GroupShare must be at least 3 bytes long")
+        } else {
+            let share = Share::try_from(&s[1..])?;
+            Ok(GroupShare { group: s[0], share })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GroupShare, GroupSharks, Sharks};
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn make_shares(sharks: &GroupSharks, secret: &[u8], members: usize) -> Vec<Vec<GroupShare>> {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        sharks
+            .dealer_rng(secret, &mut rng)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.take(members).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_insufficient_groups_err() {
+        let sharks = GroupSharks {
+            threshold: 2,
+            groups: vec![Sharks(3), Sharks(2)],
+        };
+        let shares = make_shares(&sharks, &[1, 2], 3);
+        let mixed: Vec<&GroupShare> = shares[0].iter().chain(&shares[1][..1]).collect();
+        assert_eq!(sharks.satisfied_groups(mixed.clone()), Ok(vec![0]));
+        assert!(sharks.recover(mixed).is_err());
+    }
+
+    #[test]
+    fn test_inconsistent_group_err() {
+        let sharks = GroupSharks {
+            threshold: 1,
+            groups: vec![Sharks(2), Sharks(2)],
+        };
+        let mut shares = make_shares(&sharks, &[1, 2], 2);
+        assert!(sharks.recover(&shares[1]).is_ok());
+        shares[1][1].share.y.pop();
+        assert_eq!(
+            sharks.satisfied_groups(&shares[1]),
+            Err("All shares must have the same length")
+        );
+        assert_eq!(
+            sharks.recover(&shares[1]).err(),
+            Some("All shares must have the same length")
+        );
+    }
+
+    #[test]
+    fn test_too_many_groups_err() {
+        let sharks = GroupSharks {
+            threshold: 1,
+            groups: (0..256).map(|_| Sharks(1)).collect(),
+        };
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        assert!(sharks.dealer_rng(&[1, 2], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_unknown_group_err() {
+        let sharks = GroupSharks {
+            threshold: 1,
+            groups: vec![Sharks(1)],
+        };
+        let mut shares = make_shares(&sharks, &[1, 2], 1);
+        shares[0][0].group = 1;
+        assert!(sharks.recover(&shares[0]).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let sharks = GroupSharks {
+            threshold: 3,
+            groups: vec![Sharks(2), Sharks(5), Sharks(1), Sharks(4)],
+        };
+        let shares = make_shares(&sharks, &[1, 2, 3, 4], 5);
+        let mixed: Vec<&GroupShare> = shares[3]
+            .iter()
+            .chain(&shares[1])
+            .chain(&shares[0][..1])
+            .chain(&shares[2])
+            .collect();
+        assert_eq!(sharks.satisfied_groups(mixed.clone()), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            sharks.recover(mixed).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn group_share_serialization_works() {
+        let sharks = GroupSharks {
+            threshold: 1,
+            groups: vec![Sharks(1), Sharks(2)],
+        };
+        let shares = make_shares(&sharks, &[1, 2], 2);
+        let bytes = Vec::from(&shares[1][1]);
+        assert_eq!(bytes[0], 1);
+        let share = GroupShare::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(share.group, 1);
+        assert_eq!(Vec::from(&share), bytes);
+        assert!(GroupShare::try_from(&[1, 2][..]).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use alloc::vec::Vec;
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::GF256;
+use super::math;
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// Tuple struct which implements [computational secret sharing](https://doi.org/10.1007/3-540-48329-2_12),
+/// suitable for large secrets. Its only parameter is the minimum shares threshold.
+///
+/// The secret is encrypted with ChaCha20-Poly1305 under a random key, and only the 32 bytes key is shared
+/// with `Sharks`. The ciphertext is dispersed with an erasure code, so each share costs about `|secret| / k`
+/// bytes plus the key share, instead of `|secret|` bytes.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ HybridSharks, HybridShare };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // Set a minimum threshold of 3 shares
+/// let sharks = HybridSharks(3);
+/// // Obtain an iterator over the shares for a large secret
+/// let secret = vec![42; 3000];
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let dealer = sharks.dealer_rng(&secret, &mut rng).unwrap();
+/// // Get 5 shares, each one around a third of the secret size
+/// let shares: Vec<HybridShare> = dealer.take(5).collect();
+/// assert!(Vec::from(&shares[0]).len() < 1100);
+/// // Recover the original secret!
+/// let recovered = sharks.recover(&shares[2..]).unwrap();
+/// assert_eq!(recovered.expose_secret(), secret);
+/// ```
+pub struct HybridSharks(pub u8);
+
+/// A share used to reconstruct a secret dealt by `HybridSharks`. It holds a share of the encryption key
+/// and a fragment of the ciphertext, both at the same `x`. Can be serialized to and from a byte array.
+#[derive(Clone)]
+pub struct HybridShare {
+    pub key: Share,
+    pub payload: Share,
+}
+
+impl HybridSharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares.
+    /// The maximum number of shares that can be generated is 255.
+    /// If the threshold is zero, or the secret is too large to be encrypted, an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ HybridSharks, HybridShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = HybridSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(&[1, 2], &mut rng).unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<HybridShare> = dealer.take(3).collect();
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = HybridShare>, &'static str> {
+        if self.0 == 0 {
+            return Err("Threshold can not be zero");
+        }
+
+        let mut key = [0u8; KEY_LENGTH];
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut key);
+        rng.fill_bytes(&mut nonce);
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), secret);
+        let keys = Sharks(self.0).dealer_rng(&key, rng);
+        #[cfg(feature = "zeroize_memory")]
+        key.zeroize();
+
+        let mut payload = Vec::with_capacity(NONCE_LENGTH + secret.len() + 16);
+        payload.extend_from_slice(&nonce);
+        payload.extend(ciphertext.map_err(|_| "Secret is too large to be encrypted")?);
+
+        Ok(keys
+            .zip(math::disperse(&payload, self.0))
+            .map(|(key, payload)| HybridShare { key, payload }))
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares.
+    /// The maximum number of shares that can be generated is 255.
+    /// If the threshold is zero, or the secret is too large to be encrypted, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ HybridSharks, HybridShare };
+    /// # let sharks = HybridSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let dealer = sharks.dealer(&[1, 2]).unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<HybridShare> = dealer.take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<impl Iterator<Item = HybridShare>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret.
+    /// If the number of distinct shares is less than the minimum threshold, or the recovered
+    /// ciphertext can not be authenticated, an `Err` is returned, otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ HybridSharks, HybridShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = HybridSharks(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<HybridShare> = sharks.dealer_rng(&[1], &mut rng).unwrap().take(3).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove shares for demonstration purposes
+    /// shares.clear();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough shares to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a HybridShare>,
+        T::IntoIter: Iterator<Item = &'a HybridShare>,
+    {
+        let mut payload_length: Option<usize> = None;
+        let mut keys: Vec<&Share> = Vec::new();
+        let mut payloads: Vec<&Share> = Vec::new();
+
+        for share in shares.into_iter() {
+            if payload_length.is_none() {
+                payload_length = Some(share.payload.y.len());
+            }
+
+            if Some(share.payload.y.len()) != payload_length {
+                return Err("All shares must have the same length");
+            } else {
+                keys.push(&share.key);
+                payloads.push(&share.payload);
+            }
+        }
+
+        let key = Sharks(self.0).recover(keys)?;
+        if key.expose_secret().len() != KEY_LENGTH {
+            return Err("Invalid key share length");
+        }
+        let payload = math::reconstruct(&payloads, self.0)?;
+        if payload.len() < NONCE_LENGTH {
+            return Err("Invalid payload length");
+        }
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.expose_secret()));
+        let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map(Secret::from)
+            .map_err(|_| "Could not authenticate the recovered secret")
+    }
+}
+
+/// Obtains a byte vector from a `HybridShare` instance
+impl From<&HybridShare> for Vec<u8> {
+    fn from(s: &HybridShare) -> Vec<u8> {
+        let mut bytes = Vec::from(&s.key);
+        bytes.extend(s.payload.y.iter().map(|p| p.0));
+        bytes
+    }
+}
+
+/// Obtains a `HybridShare` instance from a byte slice
+impl core::convert::TryFrom<&[u8]> for HybridShare {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<HybridShare, Self::Error> {
+        if s.len() < KEY_LENGTH + 2 {
+            Err("A HybridShare must be at least 34 bytes long")
+        } else {
+            let key = Share::try_from(&s[..=KEY_LENGTH])?;
+            let payload = Share {
+                x: GF256(s[0]),
+                y: s[KEY_LENGTH + 1..].iter().map(|p| GF256(*p)).collect(),
+            };
+            Ok(HybridShare { key, payload })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HybridShare, HybridSharks};
+    use alloc::vec::Vec;
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    #[test]
+    fn test_integration_works() {
+        let sharks = HybridSharks(5);
+        let secret: Vec<u8> = (0..=255).collect();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<HybridShare> = sharks
+            .dealer_rng(&secret, &mut rng)
+            .unwrap()
+            .take(8)
+            .collect();
+        assert_eq!(shares[0].payload.y.len(), 57);
+        assert_eq!(
+            sharks.recover(&shares[3..]).unwrap().expose_secret(),
+            secret
+        );
+    }
+
+    #[test]
+    fn test_insufficient_shares_err() {
+        let sharks = HybridSharks(5);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<HybridShare> = sharks
+            .dealer_rng(&[1, 2, 3], &mut rng)
+            .unwrap()
+            .take(4)
+            .collect();
+        assert!(sharks.recover(&shares).is_err());
+    }
+
+    #[test]
+    fn test_tampered_payload_err() {
+        let sharks = HybridSharks(2);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let mut shares: Vec<HybridShare> = sharks
+            .dealer_rng(&[1, 2, 3], &mut rng)
+            .unwrap()
+            .take(2)
+            .collect();
+        shares[0].payload.y[0].0 ^= 1;
+        assert!(sharks.recover(&shares).is_err());
+    }
+
+    #[test]
+    fn test_zero_threshold_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        assert!(HybridSharks(0).dealer_rng(&[1, 2, 3], &mut rng).is_err());
+    }
+
+    #[test]
+    fn hybrid_share_serialization_works() {
+        let sharks = HybridSharks(2);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<HybridShare> = sharks
+            .dealer_rng(&[1, 2, 3], &mut rng)
+            .unwrap()
+            .take(2)
+            .map(|s| HybridShare::try_from(Vec::from(&s).as_slice()).unwrap())
+            .collect();
+        assert_eq!(sharks.recover(&shares).unwrap().expose_secret(), &[1, 2, 3]);
+        assert!(HybridShare::try_from(&[1; 33][..]).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+
+use super::math;
+use super::share::Share;
+
+/// Tuple struct which implements [Rabin's Information Dispersal Algorithm](https://doi.org/10.1145/62044.62050)
+/// over the same 256 bits Galois Field as `Sharks`. Its only parameter is the minimum fragments threshold.
+///
+/// Unlike Shamir's shares, fragments are only `|data| / k` bytes long, but they offer no secrecy at all:
+/// use it to spread non-secret data among storage nodes.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ Ida, Share };
+/// // Set a minimum threshold of 4 fragments
+/// let ida = Ida(4);
+/// // Obtain an iterator over the fragments for data [1, 2, 3, 4, 5, 6, 7, 8]
+/// let dispersal = ida.disperse(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+/// // Get 6 fragments, each one 3 bytes long
+/// let fragments: Vec<Share> = dispersal.take(6).collect();
+/// assert_eq!(Vec::from(&fragments[0]).len(), 1 + 3);
+/// // Reconstruct the original data from any 4 of them!
+/// let data = ida.recover(&fragments[2..]).unwrap();
+/// assert_eq!(data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+/// ```
+pub struct Ida(pub u8);
+
+impl Ida {
+    /// Given a `data` byte slice, returns an `Iterator` along new fragments.
+    /// The maximum number of fragments that can be generated is 255.
+    /// If the threshold is zero an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Ida, Share };
+    /// # let ida = Ida(3);
+    /// // Obtain an iterator over the fragments for data [1, 2]
+    /// let dispersal = ida.disperse(&[1, 2]).unwrap();
+    /// // Get 3 fragments
+    /// let fragments: Vec<Share> = dispersal.take(3).collect();
+    pub fn disperse(&self, data: &[u8]) -> Result<impl Iterator<Item = Share>, &'static str> {
+        if self.0 == 0 {
+            return Err("Threshold can not be zero");
+        }
+
+        Ok(math::disperse(data, self.0))
+    }
+
+    /// Given an iterable collection of fragments, recovers the original data.
+    /// If the number of distinct fragments is less than the minimum threshold an `Err` is returned,
+    /// otherwise an `Ok` containing the data.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Ida, Share };
+    /// # let ida = Ida(3);
+    /// # let mut fragments: Vec<Share> = ida.disperse(&[1]).unwrap().take(3).collect();
+    /// // Recover original data from fragments
+    /// let mut data = ida.recover(&fragments);
+    /// // Data correctly recovered
+    /// assert!(data.is_ok());
+    /// // Remove fragments for demonstration purposes
+    /// fragments.clear();
+    /// data = ida.recover(&fragments);
+    /// // Not enough fragments to recover data
+    /// assert!(data.is_err());
+    pub fn recover<'a, T>(&self, fragments: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: IntoIterator<Item = &'a Share>,
+        T::IntoIter: Iterator<Item = &'a Share>,
+    {
+        let mut fragment_length: Option<usize> = None;
+        let mut values: Vec<&Share> = Vec::new();
+
+        for fragment in fragments.into_iter() {
+            if fragment_length.is_none() {
+                fragment_length = Some(fragment.y.len());
+            }
+
+            if Some(fragment.y.len()) != fragment_length {
+                return Err("All fragments must have the same length");
+            } else {
+                values.push(fragment);
+            }
+        }
+
+        math::reconstruct(&values, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ida, Share};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_insufficient_fragments_err() {
+        let ida = Ida(10);
+        let fragments: Vec<Share> = ida.disperse(&[1, 2, 3]).unwrap().take(9).collect();
+        assert!(ida.recover(&fragments).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_fragments_err() {
+        let ida = Ida(3);
+        let mut fragments: Vec<Share> = ida.disperse(&[1, 2, 3]).unwrap().take(3).collect();
+        fragments[1] = fragments[0].clone();
+        assert!(ida.recover(&fragments).is_err());
+    }
+
+    #[test]
+    fn test_different_length_err() {
+        let ida = Ida(2);
+        let mut fragments: Vec<Share> = ida.disperse(&[1, 2, 3]).unwrap().take(2).collect();
+        fragments[1].y.pop();
+        assert!(ida.recover(&fragments).is_err());
+    }
+
+    #[test]
+    fn test_zero_threshold_err() {
+        assert!(Ida(0).disperse(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let ida = Ida(255);
+        let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let fragments: Vec<Share> = ida.disperse(&data).unwrap().take(255).collect();
+        assert_eq!(fragments[0].y.len(), 4);
+        assert_eq!(ida.recover(&fragments).unwrap(), data);
+    }
+}
@@ -2,24 +2,26 @@
 //!
 //! Usage example (std):
 //! ```
+//! # #[cfg(feature = "std")]
+//! # {
 //! use sharks::{ Sharks, Share };
 //!
 //! // Set a minimum threshold of 10 shares
 //! let sharks = Sharks(10);
 //! // Obtain an iterator over the shares for secret [1, 2, 3, 4]
-//! # #[cfg(feature = "std")]
-//! # {
 //! let dealer = sharks.dealer(&[1, 2, 3, 4]);
 //! // Get 10 shares
 //! let shares: Vec<Share> = dealer.take(10).collect();
 //! // Recover the original secret!
 //! let secret = sharks.recover(shares.as_slice()).unwrap();
-//! assert_eq!(secret, vec![1, 2, 3, 4]);
+//! assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
 //! # }
 //! ```
 //!
-//! Usage example (no std):
+//! Usage example (no std, with the `alloc` feature):
 //! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
 //! use sharks::{ Sharks, Share };
 //! use rand_chacha::rand_core::SeedableRng;
 //!
@@ -32,43 +34,119 @@
 //! let shares: Vec<Share> = dealer.take(10).collect();
 //! // Recover the original secret!
 //! let secret = sharks.recover(shares.as_slice()).unwrap();
-//! assert_eq!(secret, vec![1, 2, 3, 4]);
+//! assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+//! # }
 //! ```
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+mod additive;
+#[cfg(feature = "alloc")]
+pub mod dkg;
+#[cfg(feature = "envelope")]
+mod envelope;
+#[cfg(feature = "alloc")]
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 mod field;
+pub mod fixed;
+#[cfg(feature = "alloc")]
+mod gf2n;
+#[cfg(feature = "std")]
+pub mod gfsplit;
+#[cfg(feature = "alloc")]
+mod group;
+#[cfg(feature = "hybrid")]
+mod hybrid;
+#[cfg(feature = "alloc")]
+mod ida;
+#[cfg(feature = "alloc")]
 mod math;
+#[cfg(feature = "alloc")]
+mod memory;
+#[cfg(feature = "alloc")]
+pub mod mpc;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "alloc")]
+mod ramp;
+#[cfg(feature = "alloc")]
+mod secret;
+#[cfg(feature = "alloc")]
+mod secretsjs;
+#[cfg(feature = "alloc")]
 mod share;
+#[cfg(feature = "alloc")]
+mod ssss;
+#[cfg(feature = "alloc")]
+mod vault;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "alloc")]
+mod weighted;
 
+#[cfg(any(feature = "alloc", test))]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
-use hashbrown::HashSet;
 
+#[cfg(feature = "alloc")]
+pub use additive::AdditiveSharks;
+#[cfg(feature = "envelope")]
+pub use envelope::ShareEnvelope;
+#[cfg(feature = "alloc")]
+pub use error::Error;
+#[cfg(feature = "alloc")]
 use field::GF256;
+#[cfg(feature = "alloc")]
+pub use group::{GroupShare, GroupSharks};
+#[cfg(feature = "hybrid")]
+pub use hybrid::{HybridShare, HybridSharks};
+#[cfg(feature = "alloc")]
+pub use ida::Ida;
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+pub use memory::lock_failures;
+#[cfg(feature = "alloc")]
+use memory::SecretVec;
+#[cfg(feature = "alloc")]
+pub use ramp::RampSharks;
+#[cfg(feature = "alloc")]
+pub use secret::Secret;
+#[cfg(feature = "alloc")]
+pub use secretsjs::{SecretsJsShare, SecretsJsSharks};
+#[cfg(feature = "alloc")]
 pub use share::Share;
+#[cfg(feature = "alloc")]
+pub use ssss::{SsssShare, SsssSharks};
+#[cfg(feature = "alloc")]
+pub use vault::{VaultShare, VaultSharks};
+#[cfg(feature = "alloc")]
+pub use weighted::{ShareBundle, WeightedSharks};
 
 /// Tuple struct which implements methods to generate shares and recover secrets over a 256 bits Galois Field.
 /// Its only parameter is the minimum shares threshold.
 ///
 /// Usage example:
 /// ```
+/// # #[cfg(feature = "std")]
+/// # {
 /// # use sharks::{ Sharks, Share };
 /// // Set a minimum threshold of 10 shares
 /// let sharks = Sharks(10);
 /// // Obtain an iterator over the shares for secret [1, 2, 3, 4]
-/// # #[cfg(feature = "std")]
-/// # {
 /// let dealer = sharks.dealer(&[1, 2, 3, 4]);
 /// // Get 10 shares
 /// let shares: Vec<Share> = dealer.take(10).collect();
 /// // Recover the original secret!
 /// let secret = sharks.recover(shares.as_slice()).unwrap();
-/// assert_eq!(secret, vec![1, 2, 3, 4]);
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
 /// # }
 /// ```
 pub struct Sharks(pub u8);
 
+#[cfg(feature = "alloc")]
 impl Sharks {
     /// This method is useful when `std` is not available. For typical usage
     /// see the `dealer` method.
@@ -92,13 +170,51 @@ impl Sharks {
         secret: &[u8],
         rng: &mut R,
     ) -> impl Iterator<Item = Share> {
-        let mut polys = Vec::with_capacity(secret.len());
+        let mut polys = SecretVec::with_capacity(secret.len() * self.0 as usize);
 
         for chunk in secret {
-            polys.push(math::random_polynomial(GF256(*chunk), self.0, rng))
+            math::random_polynomial(GF256(*chunk), self.0, rng, &mut polys);
         }
 
-        math::get_evaluator(polys)
+        math::get_evaluator(polys, self.0)
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares deterministically derived
+    /// from a 32 bytes `seed` and a `context` number, so that they can be re-derived later on, for instance
+    /// during a key ceremony. The output is stable across releases of this crate, and checked against
+    /// the known-answer vectors in `vectors/dealer_from_seed.txt`.
+    ///
+    /// The random bytes are the ChaCha20 keystream for key `seed`, 64 bits nonce `context` (little endian)
+    /// and 64 bits block counter starting at zero. For each secret byte in order, its polynomial coefficients
+    /// are taken from the next non-zero keystream bytes, from the highest degree down to the linear term.
+    /// Shares are then evaluated at `x = 1, 2, ..` as with `dealer_rng`.
+    ///
+    /// The seed must be kept as secret as the secret itself, and must never be reused under
+    /// the same context for a different secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Sharks, Share };
+    /// # let sharks = Sharks(3);
+    /// // Obtain the shares for secret [1, 2] twice
+    /// let first: Vec<Share> = sharks.dealer_from_seed(&[1, 2], [0x90; 32], 1).take(3).collect();
+    /// let again: Vec<Share> = sharks.dealer_from_seed(&[1, 2], [0x90; 32], 1).take(3).collect();
+    /// // The shares are the same
+    /// assert!(first.iter().zip(again.iter()).all(|(a, b)| Vec::from(a) == Vec::from(b)));
+    pub fn dealer_from_seed(
+        &self,
+        secret: &[u8],
+        seed: [u8; 32],
+        context: u64,
+    ) -> impl Iterator<Item = Share> {
+        let mut stream = math::keystream(seed, context);
+        let mut polys = SecretVec::with_capacity(secret.len() * self.0 as usize);
+
+        for chunk in secret {
+            math::stream_polynomial(GF256(*chunk), self.0, &mut stream, &mut polys);
+        }
+
+        math::get_evaluator(polys, self.0)
     }
 
     /// Given a `secret` byte slice, returns an `Iterator` along new shares.
@@ -119,8 +235,9 @@ impl Sharks {
     }
 
     /// Given an iterable collection of shares, recovers the original secret.
-    /// If the number of distinct shares is less than the minimum threshold an `Err` is returned,
-    /// otherwise an `Ok` containing the secret.
+    /// If the number of distinct shares is less than the minimum threshold, or shares at the same `x`
+    /// differ, an `Err` is returned, otherwise an `Ok` containing the `Secret`.
+    /// Exact duplicates are skipped.
     ///
     /// Example:
     /// ```
@@ -138,14 +255,13 @@ impl Sharks {
     /// secret = sharks.recover(&shares);
     /// // Not enough shares to recover secret
     /// assert!(secret.is_err());
-    pub fn recover<'a, T>(&self, shares: T) -> Result<Vec<u8>, &str>
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, Error>
     where
         T: IntoIterator<Item = &'a Share>,
         T::IntoIter: Iterator<Item = &'a Share>,
     {
         let mut share_length: Option<usize> = None;
-        let mut keys: HashSet<u8> = HashSet::new();
-        let mut values: Vec<Share> = Vec::new();
+        let mut values: Vec<&Share> = Vec::new();
 
         for share in shares.into_iter() {
             if share_length.is_none() {
@@ -153,25 +269,83 @@ impl Sharks {
             }
 
             if Some(share.y.len()) != share_length {
-                return Err("All shares must have the same length");
-            } else {
-                keys.insert(share.x.0);
-                values.push(share.clone());
+                return Err(Error::InconsistentShares);
+            }
+            // Exact duplicates are only interpolated once
+            match values.iter().find(|s| s.x == share.x) {
+                Some(s) if s.y[..] != share.y[..] => return Err(Error::ConflictingShares),
+                Some(_) => {}
+                None => values.push(share),
             }
         }
 
-        if keys.is_empty() || (keys.len() < self.0 as usize) {
-            Err("Not enough shares to recover original secret")
+        if values.is_empty() || (values.len() < self.0 as usize) {
+            Err(Error::NotEnoughShares)
         } else {
-            Ok(math::interpolate(values.as_slice()))
+            Ok(Secret(math::interpolate(values.as_slice())))
+        }
+    }
+
+    /// Given an iterable collection of shares, checks that they can recover a secret, without recovering it.
+    /// Shares must have the same length, shares at the same `x` must be equal, and there must be at least
+    /// as many distinct shares as the minimum threshold. Shares beyond the threshold must lie on the same
+    /// polynomials as the first ones, which is checked by interpolating at their `x`, never at zero.
+    /// Returns an `Err` describing the first failed check.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ Sharks, Share };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = Sharks(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let mut shares: Vec<Share> = sharks.dealer_rng(&[1, 2], &mut rng).take(4).collect();
+    /// assert!(sharks.verify(&shares).is_ok());
+    /// // Corrupt the last share
+    /// shares[3] = &shares[3] * 2;
+    /// assert!(sharks.verify(&shares).is_err());
+    pub fn verify<'a, T>(&self, shares: T) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = &'a Share>,
+        T::IntoIter: Iterator<Item = &'a Share>,
+    {
+        let mut share_length: Option<usize> = None;
+        let mut values: Vec<&Share> = Vec::new();
+
+        for share in shares.into_iter() {
+            if share_length.is_none() {
+                share_length = Some(share.y.len());
+            }
+
+            if Some(share.y.len()) != share_length {
+                return Err(Error::InconsistentShares);
+            }
+            match values.iter().find(|s| s.x == share.x) {
+                Some(s) if s.y[..] != share.y[..] => return Err(Error::ConflictingShares),
+                Some(_) => {}
+                None => values.push(share),
+            }
+        }
+
+        if values.is_empty() || (values.len() < self.0 as usize) {
+            return Err(Error::NotEnoughShares);
+        }
+
+        let (base, rest) = values.split_at(core::cmp::max(self.0, 1) as usize);
+        for share in rest {
+            let y = math::interpolate_at(base, share.x.clone());
+            if y.iter().zip(share.y.iter()).any(|(a, b)| *a != b.0) {
+                return Err(Error::NotOnPolynomial);
+            }
         }
+        Ok(())
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-    use super::{Share, Sharks};
-    use alloc::{vec, vec::Vec};
+    use super::{Error, Share, Sharks};
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     impl Sharks {
         #[cfg(not(feature = "std"))]
@@ -208,11 +382,95 @@ mod tests {
         assert!(secret.is_err());
     }
 
+    #[test]
+    fn test_duplicate_shares_are_skipped() {
+        let sharks = Sharks(2);
+        let shares: Vec<Share> = sharks.make_shares(&[1, 2]).take(2).collect();
+        let duplicated = [&shares[0], &shares[0], &shares[1]];
+        let secret = sharks.recover(duplicated.iter().cloned()).unwrap();
+        assert_eq!(secret.expose_secret(), &[1, 2]);
+        assert_eq!(
+            sharks.recover(vec![&shares[0], &shares[0]]).unwrap_err(),
+            Error::NotEnoughShares
+        );
+    }
+
+    #[test]
+    fn test_conflicting_shares_err() {
+        let sharks = Sharks(2);
+        let shares: Vec<Share> = sharks.make_shares(&[1, 2]).take(2).collect();
+        let conflicting = &shares[0] * 2;
+        assert_eq!(
+            sharks
+                .recover(vec![&shares[0], &conflicting, &shares[1]])
+                .unwrap_err(),
+            Error::ConflictingShares
+        );
+    }
+
     #[test]
     fn test_integration_works() {
         let sharks = Sharks(255);
         let shares: Vec<Share> = sharks.make_shares(&[1, 2, 3, 4]).take(255).collect();
         let secret = sharks.recover(&shares).unwrap();
-        assert_eq!(secret, vec![1, 2, 3, 4]);
+        assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn verify_works() {
+        let sharks = Sharks(3);
+        let mut shares: Vec<Share> = sharks.make_shares(&[1, 2, 3]).take(5).collect();
+        assert!(sharks.verify(&shares).is_ok());
+        assert!(sharks.verify(&shares[..3]).is_ok());
+        assert!(sharks.verify(&shares[..2]).is_err());
+
+        shares[4] = Share {
+            x: shares[0].x.clone(),
+            y: shares[0].y.clone(),
+        };
+        assert!(sharks.verify(&shares).is_ok());
+        shares[4] = &shares[1] * 2;
+        assert!(sharks.verify(&shares).is_err());
+        shares[3].y.pop();
+        assert!(sharks.verify(&shares[..4]).is_err());
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn dealer_from_seed_matches_vectors() {
+        let vectors = include_str!("../vectors/dealer_from_seed.txt");
+        let mut checked = 0;
+
+        for vector in vectors.split("\n\n").skip(1) {
+            let mut fields = vector.lines().map(|l| {
+                let mut kv = l.splitn(2, '=');
+                (kv.next().unwrap().trim(), kv.next().unwrap().trim())
+            });
+            let threshold: u8 = fields.next().unwrap().1.parse().unwrap();
+            let mut seed = [0u8; 32];
+            seed.copy_from_slice(&from_hex(fields.next().unwrap().1));
+            let context: u64 = fields.next().unwrap().1.parse().unwrap();
+            let secret = from_hex(fields.next().unwrap().1);
+            let expected: Vec<Vec<u8>> = fields.map(|(_, share)| from_hex(share)).collect();
+
+            let sharks = Sharks(threshold);
+            let shares: Vec<Share> = sharks
+                .dealer_from_seed(&secret, seed, context)
+                .take(expected.len())
+                .collect();
+            let bytes: Vec<Vec<u8>> = shares.iter().map(Vec::from).collect();
+            assert_eq!(bytes, expected);
+            let recovered = sharks.recover(&shares[..threshold as usize]).unwrap();
+            assert_eq!(recovered.expose_secret(), secret.as_slice());
+            checked += 1;
+        }
+
+        assert_eq!(checked, 5);
     }
 }
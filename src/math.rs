@@ -1,59 +1,107 @@
 // A module which contains necessary algorithms to compute Shamir's shares and recover secrets
 
 use alloc::vec::Vec;
+use core::cmp;
+use hashbrown::HashSet;
 
 use rand::distributions::{Distribution, Uniform};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
 use super::field::GF256;
+use super::memory::SecretVec;
 use super::share::Share;
 
 // Finds the [root of the Lagrange polynomial](https://en.wikipedia.org/wiki/Shamir%27s_Secret_Sharing#Computationally_efficient_approach).
 // The expected `shares` argument format is the same as the output by the `get_evaluator´ function.
 // Where each (key, value) pair corresponds to one share, where the key is the `x` and the value is a vector of `y`,
 // where each element corresponds to one of the secret's byte chunks.
-pub fn interpolate(shares: &[Share]) -> Vec<u8> {
-    (0..shares[0].y.len())
-        .map(|s| {
-            shares
-                .iter()
-                .map(|s_i| {
-                    shares
-                        .iter()
-                        .filter(|s_j| s_j.x != s_i.x)
-                        .map(|s_j| s_j.x.clone() / (s_j.x.clone() - s_i.x.clone()))
-                        .product::<GF256>()
-                        * s_i.y[s].clone()
-                })
-                .sum::<GF256>()
-                .0
+pub fn interpolate(shares: &[&Share]) -> SecretVec<u8> {
+    interpolate_at(shares, GF256(0))
+}
+
+// Evaluates at `x` the polynomials passing through the `shares` points, one per secret's byte chunk.
+// The result is written into a buffer of the exact size, so that no copies are left behind by reallocations,
+// and the weighted `y` are added up in place, so that partial sums only ever live in that buffer.
+pub fn interpolate_at(shares: &[&Share], x: GF256) -> SecretVec<u8> {
+    let xs: Vec<GF256> = shares.iter().map(|s| s.x.clone()).collect();
+    let coefficients = lagrange_coefficients(&xs, x);
+    let mut result: SecretVec<u8> = shares[0].y.iter().map(|_| 0).collect();
+
+    for (s_i, l_i) in shares.iter().zip(coefficients.iter()) {
+        for (r, y) in result.iter_mut().zip(s_i.y.iter()) {
+            *r ^= (l_i.clone() * y.clone()).0;
+        }
+    }
+
+    result
+}
+
+// Returns the values at `x` of the Lagrange basis polynomials for the given `xs`, so that
+// `f(x) = l_0(x) * f(x_0) + l_1(x) * f(x_1) + ..` for any polynomial `f` of degree lower than `xs.len()`.
+pub fn lagrange_coefficients(xs: &[GF256], x: GF256) -> Vec<GF256> {
+    xs.iter()
+        .map(|x_i| {
+            xs.iter()
+                .filter(|x_j| *x_j != x_i)
+                .map(|x_j| (x.clone() - x_j.clone()) / (x_i.clone() - x_j.clone()))
+                .product()
         })
         .collect()
 }
 
-// Generates `k` polynomial coefficients, being the last one `s` and the others randomly generated between `[1, 255]`.
-// Coefficient degrees go from higher to lower in the returned vector order.
-pub fn random_polynomial<R: rand::Rng>(s: GF256, k: u8, rng: &mut R) -> Vec<GF256> {
-    let k = k as usize;
-    let mut poly = Vec::with_capacity(k);
+// Appends `k` polynomial coefficients to `polys`, being the last one `s` and the others randomly generated
+// between `[1, 255]`. Coefficient degrees go from higher to lower in the vector order.
+// The polynomials of every secret byte are stored one after another, so that they take a single allocation.
+pub fn random_polynomial<R: rand::Rng>(s: GF256, k: u8, rng: &mut R, polys: &mut SecretVec<GF256>) {
     let between = Uniform::new_inclusive(1, 255);
 
     for _ in 1..k {
-        poly.push(GF256(between.sample(rng)));
+        polys.push(GF256(between.sample(rng)));
     }
-    poly.push(s);
+    polys.push(s);
+}
 
-    poly
+// The deterministic random bit generator used by `dealer_from_seed`: the ChaCha20 keystream for key `seed`,
+// 64 bits nonce `context` and 64 bits block counter starting at zero, as in the original ChaCha definition.
+pub fn keystream(seed: [u8; 32], context: u64) -> impl Iterator<Item = u8> {
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    rng.set_stream(context);
+
+    core::iter::repeat_with(move || {
+        let mut block = [0u8; 64];
+        rng.fill_bytes(&mut block);
+        block
+    })
+    .flat_map(IntoIterator::into_iter)
 }
 
-// Returns an iterator over the points of the `polys` polynomials passed as argument.
+// Appends `k` polynomial coefficients to `polys` like `random_polynomial`, but taking the random ones
+// from the `stream` bytes and skipping zeros. Unlike the `rand` distributions, its output is stable by definition.
+pub fn stream_polynomial<I: Iterator<Item = u8>>(
+    s: GF256,
+    k: u8,
+    stream: &mut I,
+    polys: &mut SecretVec<GF256>,
+) {
+    for _ in 1..k {
+        let coefficient = stream.find(|b| *b != 0).expect("The keystream is endless");
+        polys.push(GF256(coefficient));
+    }
+    polys.push(s);
+}
+
+// Returns an iterator over the points of the `polys` polynomials passed as argument, of `k` coefficients each
+// (or a single one if `k` is zero) and stored one after another as by `random_polynomial`.
 // Each item of the iterator is a tuple `(x, [f_1(x), f_2(x)..])` where eaxh `f_i` is the result for the ith polynomial.
 // Each polynomial corresponds to one byte chunk of the original secret.
 // The iterator will start at `x = 1` and end at `x = 255`.
-pub fn get_evaluator(polys: Vec<Vec<GF256>>) -> impl Iterator<Item = Share> {
-    (1..=u8::max_value()).map(GF256).map(move |x| Share {
+pub fn get_evaluator(polys: SecretVec<GF256>, k: u8) -> impl Iterator<Item = Share> {
+    let k = cmp::max(k as usize, 1);
+    (1..=u8::MAX).map(GF256).map(move |x| Share {
         x: x.clone(),
         y: polys
-            .iter()
+            .chunks(k)
             .map(|p| {
                 p.iter()
                     .fold(GF256(0), |acc, c| acc * x.clone() + c.clone())
@@ -62,37 +110,225 @@ pub fn get_evaluator(polys: Vec<Vec<GF256>>) -> impl Iterator<Item = Share> {
     })
 }
 
+// Returns the coefficients of the Lagrange basis polynomials for the given `xs`, one polynomial per point.
+// Coefficient degrees go from higher to lower, as in `random_polynomial`.
+// The basis polynomial `l_i` is built by dividing `N(x) = (x - x_0)..(x - x_k)` by `(x - x_i)`
+// and normalizing the quotient so that `l_i(x_i) = 1`.
+pub fn lagrange_basis(xs: &[GF256]) -> Vec<Vec<GF256>> {
+    let mut n = Vec::with_capacity(xs.len() + 1);
+    n.push(GF256(1));
+    for x_j in xs {
+        n.push(GF256(0));
+        for d in (1..n.len()).rev() {
+            n[d] = n[d].clone() + n[d - 1].clone() * x_j.clone();
+        }
+    }
+
+    xs.iter()
+        .map(|x_i| {
+            let mut q = Vec::with_capacity(xs.len());
+            q.push(n[0].clone());
+            for c in &n[1..xs.len()] {
+                let prev = q[q.len() - 1].clone();
+                q.push(c.clone() + prev * x_i.clone());
+            }
+
+            let denominator = q
+                .iter()
+                .fold(GF256(0), |acc, c| acc * x_i.clone() + c.clone());
+            q.into_iter().map(|c| c / denominator.clone()).collect()
+        })
+        .collect()
+}
+
+// Appends a `0x80` byte followed by as many zeros as needed for `data` to be a multiple of `k` bytes long.
+pub fn pad(data: &[u8], k: usize) -> Vec<u8> {
+    let len = (data.len() / k + 1) * k;
+    let mut padded = Vec::with_capacity(len);
+    padded.extend_from_slice(data);
+    padded.push(0x80);
+    padded.resize(len, 0);
+    padded
+}
+
+// Returns the length of `data` without the padding added by `pad`.
+pub fn unpadded_len(data: &[u8]) -> Result<usize, &'static str> {
+    match data.iter().rposition(|b| *b != 0) {
+        Some(i) if data[i] == 0x80 => Ok(i),
+        _ => Err("Invalid padding"),
+    }
+}
+
+// Removes the padding added by `pad`.
+pub fn unpad(mut data: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+    data.truncate(unpadded_len(&data)?);
+    Ok(data)
+}
+
+// Disperses `data` into fragments of `|data| / k` bytes, any `k` of which are enough to reconstruct it.
+// The padded data is split in `k` bytes chunks, each one used as the coefficients of a polynomial which is
+// then evaluated by `get_evaluator`.
+pub fn disperse(data: &[u8], k: u8) -> impl Iterator<Item = Share> {
+    let polys = pad(data, k as usize).into_iter().map(GF256).collect();
+
+    get_evaluator(polys, k)
+}
+
+// Reconstructs the data dispersed by `disperse` from the first `k` fragments with distinct `x`.
+// Inverting the Vandermonde matrix amounts to adding up the Lagrange basis polynomials weighted by each `y`.
+pub fn reconstruct(fragments: &[&Share], k: u8) -> Result<Vec<u8>, &'static str> {
+    let mut keys: HashSet<u8> = HashSet::new();
+    let fragments: Vec<&Share> = fragments
+        .iter()
+        .copied()
+        .filter(|f| keys.insert(f.x.0))
+        .take(k as usize)
+        .collect();
+
+    if k == 0 || fragments.len() < k as usize {
+        return Err("Not enough fragments to reconstruct original data");
+    }
+
+    let xs: Vec<GF256> = fragments.iter().map(|f| f.x.clone()).collect();
+    let basis = lagrange_basis(&xs);
+    let mut data = Vec::with_capacity(fragments[0].y.len() * k as usize);
+
+    for g in 0..fragments[0].y.len() {
+        for d in 0..k as usize {
+            data.push(
+                fragments
+                    .iter()
+                    .zip(basis.iter())
+                    .map(|(f, l)| f.y[g].clone() * l[d].clone())
+                    .sum::<GF256>()
+                    .0,
+            );
+        }
+    }
+
+    unpad(data)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_evaluator, interpolate, random_polynomial, Share, GF256};
+    use super::{
+        disperse, get_evaluator, interpolate, interpolate_at, keystream, lagrange_basis,
+        lagrange_coefficients, pad, random_polynomial, reconstruct, stream_polynomial, unpad,
+        unpadded_len, SecretVec, Share, GF256,
+    };
     use alloc::{vec, vec::Vec};
     use rand_chacha::rand_core::SeedableRng;
 
     #[test]
     fn random_polynomial_works() {
         let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
-        let poly = random_polynomial(GF256(1), 3, &mut rng);
-        assert_eq!(poly.len(), 3);
-        assert_eq!(poly[2], GF256(1));
+        let mut polys = SecretVec::new();
+        random_polynomial(GF256(1), 3, &mut rng, &mut polys);
+        random_polynomial(GF256(2), 3, &mut rng, &mut polys);
+        assert_eq!(polys.len(), 6);
+        assert_eq!(polys[2], GF256(1));
+        assert_eq!(polys[5], GF256(2));
+    }
+
+    #[test]
+    fn keystream_works() {
+        // ChaCha20 keystream for the all zero key and nonce
+        let block: Vec<u8> = keystream([0; 32], 0).take(16).collect();
+        assert_eq!(
+            block,
+            vec![
+                0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90, 0x40, 0x5d, 0x6a, 0xe5, 0x53, 0x86,
+                0xbd, 0x28
+            ]
+        );
+        assert_eq!(keystream([0; 32], 0).nth(64), keystream([0; 32], 0).nth(64));
+        assert_ne!(keystream([0; 32], 0).nth(64), keystream([0; 32], 1).nth(64));
+    }
+
+    #[test]
+    fn stream_polynomial_works() {
+        let mut stream = vec![0, 7, 0, 0, 9, 4].into_iter();
+        let mut polys = SecretVec::new();
+        stream_polynomial(GF256(1), 3, &mut stream, &mut polys);
+        assert_eq!(polys[..], [GF256(7), GF256(9), GF256(1)]);
+        assert_eq!(stream.next(), Some(4));
     }
 
     #[test]
     fn evaluator_works() {
-        let iter = get_evaluator(vec![vec![GF256(3), GF256(2), GF256(5)]]);
-        let values: Vec<_> = iter.take(2).map(|s| (s.x.clone(), s.y.clone())).collect();
+        let polys = [3, 2, 5, 1, 0, 7].iter().copied().map(GF256).collect();
+        let iter = get_evaluator(polys, 3);
+        let values: Vec<_> = iter.take(2).map(|s| (s.x.clone(), s.y.to_vec())).collect();
         assert_eq!(
             values,
-            vec![(GF256(1), vec![GF256(4)]), (GF256(2), vec![GF256(13)])]
+            vec![
+                (GF256(1), vec![GF256(4), GF256(6)]),
+                (GF256(2), vec![GF256(13), GF256(3)])
+            ]
         );
     }
 
     #[test]
     fn interpolate_works() {
         let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
-        let poly = random_polynomial(GF256(185), 10, &mut rng);
-        let iter = get_evaluator(vec![poly]);
+        let mut polys = SecretVec::new();
+        random_polynomial(GF256(185), 10, &mut rng, &mut polys);
+        let iter = get_evaluator(polys, 10);
+        let shares: Vec<Share> = iter.take(10).collect();
+        let root = interpolate(&shares.iter().collect::<Vec<&Share>>());
+        assert_eq!(root[..], [185]);
+    }
+
+    #[test]
+    fn interpolate_at_works() {
+        let iter = get_evaluator([3, 2, 5].iter().copied().map(GF256).collect(), 3);
         let shares: Vec<Share> = iter.take(10).collect();
-        let root = interpolate(&shares);
-        assert_eq!(root, vec![185]);
+        assert_eq!(
+            interpolate_at(&shares.iter().take(3).collect::<Vec<&Share>>(), GF256(10))[..],
+            [shares[9].y[0].0]
+        );
+    }
+
+    #[test]
+    fn lagrange_coefficients_works() {
+        let xs = vec![GF256(1), GF256(2), GF256(3)];
+        assert_eq!(
+            lagrange_coefficients(&xs, GF256(2)),
+            vec![GF256(0), GF256(1), GF256(0)]
+        );
+    }
+
+    #[test]
+    fn lagrange_basis_works() {
+        let xs = vec![GF256(1), GF256(2), GF256(3)];
+        let basis = lagrange_basis(&xs);
+        for (i, l) in basis.iter().enumerate() {
+            for (j, x) in xs.iter().enumerate() {
+                let y = l
+                    .iter()
+                    .fold(GF256(0), |acc, c| acc * x.clone() + c.clone());
+                assert_eq!(y, GF256((i == j) as u8));
+            }
+        }
+    }
+
+    #[test]
+    fn pad_works() {
+        assert_eq!(pad(&[1, 2], 4), vec![1, 2, 0x80, 0]);
+        assert_eq!(pad(&[1, 2, 3, 4], 2), vec![1, 2, 3, 4, 0x80, 0]);
+        assert_eq!(unpad(vec![1, 0, 0x80, 0]), Ok(vec![1, 0]));
+        assert!(unpad(vec![1, 0, 0]).is_err());
+        assert_eq!(unpadded_len(&[1, 0x80, 0x80, 0]), Ok(2));
+        assert!(unpadded_len(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn reconstruct_works() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let fragments: Vec<Share> = disperse(&data, 4).skip(10).take(4).collect();
+        let fragments: Vec<&Share> = fragments.iter().collect();
+        assert_eq!(fragments[0].y.len(), 3);
+        assert_eq!(reconstruct(&fragments, 4), Ok(data.to_vec()));
+        assert!(reconstruct(&fragments[1..], 4).is_err());
     }
 }
@@ -0,0 +1,346 @@
+// Storage for secret material: the dealer polynomials, the shares and the recovered secrets.
+//
+// With the `mlock` feature on Linux, it is allocated in its own anonymous mapping, surrounded by
+// inaccessible guard pages, locked into RAM so that it is never swapped to disk and excluded from core dumps.
+// If the pages can not be locked, for instance because `RLIMIT_MEMLOCK` is too low, the memory is still guarded
+// and excluded from core dumps, and the failure is counted by `lock_failures`. Otherwise, it is a plain `Vec`.
+
+#[cfg(not(all(feature = "mlock", target_os = "linux")))]
+pub type SecretVec<T> = alloc::vec::Vec<T>;
+
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+pub type SecretVec<T> = LockedVec<T>;
+
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+pub use locked::{lock_failures, LockedVec};
+
+#[cfg(all(feature = "mlock", target_os = "linux"))]
+mod locked {
+    use alloc::alloc::{handle_alloc_error, Layout};
+    use core::ops::{Deref, DerefMut};
+    use core::ptr::{self, NonNull};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::{cmp, fmt, mem, slice};
+
+    #[cfg(feature = "fuzzing")]
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use zeroize::Zeroize;
+
+    // A growable array with the subset of the `Vec` API used by the crate, backed by a locked mapping.
+    pub struct LockedVec<T> {
+        ptr: NonNull<T>,
+        len: usize,
+        cap: usize,
+        // Unmapped on drop, after the elements
+        _region: Option<Region>,
+    }
+
+    // An anonymous mapping laid out as `[guard page][data pages][guard page]`.
+    struct Region {
+        base: *mut u8,
+        data: usize,
+        locked: bool,
+    }
+
+    static LOCK_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+    /// Returns how many allocations of secret material could not be locked into RAM since the process started,
+    /// for instance because `RLIMIT_MEMLOCK` was exceeded. Those are still guarded and excluded from core dumps,
+    /// but may be swapped to disk.
+    ///
+    /// Only available with the `mlock` feature on Linux.
+    pub fn lock_failures() -> usize {
+        LOCK_FAILURES.load(Ordering::Relaxed)
+    }
+
+    // The elements are owned like in a `Vec`, the mapping is never shared.
+    unsafe impl<T: Send> Send for LockedVec<T> {}
+    unsafe impl<T: Sync> Sync for LockedVec<T> {}
+
+    fn page_size() -> usize {
+        unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    }
+
+    impl Region {
+        // Maps at least `bytes` bytes of guarded memory, trying to lock them.
+        fn map(bytes: usize, layout: Layout) -> Region {
+            let page = page_size();
+            let data = bytes.div_ceil(page) * page;
+
+            unsafe {
+                let base = libc::mmap(
+                    ptr::null_mut(),
+                    data + 2 * page,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                    -1,
+                    0,
+                );
+                if base == libc::MAP_FAILED {
+                    handle_alloc_error(layout);
+                }
+
+                let base = base as *mut u8;
+                libc::mprotect(base as *mut libc::c_void, page, libc::PROT_NONE);
+                libc::mprotect(
+                    base.add(page + data) as *mut libc::c_void,
+                    page,
+                    libc::PROT_NONE,
+                );
+                libc::madvise(
+                    base.add(page) as *mut libc::c_void,
+                    data,
+                    libc::MADV_DONTDUMP,
+                );
+                // Not being able to lock the pages is not an error, but it is reported, see the module documentation
+                let locked = libc::mlock(base.add(page) as *const libc::c_void, data) == 0;
+                if !locked {
+                    LOCK_FAILURES.fetch_add(1, Ordering::Relaxed);
+                }
+
+                Region { base, data, locked }
+            }
+        }
+
+        fn data(&self) -> *mut u8 {
+            unsafe { self.base.add(page_size()) }
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            unsafe {
+                slice::from_raw_parts_mut(self.data(), self.data).zeroize();
+                libc::munmap(self.base as *mut libc::c_void, self.data + 2 * page_size());
+            }
+        }
+    }
+
+    impl<T> LockedVec<T> {
+        pub fn new() -> Self {
+            Self::with_capacity(0)
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            let size = mem::size_of::<T>();
+            if size == 0 || capacity == 0 {
+                return Self {
+                    ptr: NonNull::dangling(),
+                    len: 0,
+                    cap: if size == 0 { usize::MAX } else { 0 },
+                    _region: None,
+                };
+            }
+
+            let layout =
+                Layout::array::<T>(capacity).unwrap_or_else(|_| panic!("Capacity overflow"));
+            let region = Region::map(layout.size(), layout);
+            // The whole data pages are used, with the elements placed right before the trailing guard page
+            let cap = region.data / size;
+            let offset = (region.data - cap * size) & !(mem::align_of::<T>() - 1);
+            let ptr = unsafe { NonNull::new_unchecked(region.data().add(offset) as *mut T) };
+
+            Self {
+                ptr,
+                len: 0,
+                cap,
+                _region: Some(region),
+            }
+        }
+
+        // Whether the elements are locked into RAM, which is the case when empty as there are none
+        pub fn is_locked(&self) -> bool {
+            self._region.as_ref().is_none_or(|region| region.locked)
+        }
+
+        pub fn push(&mut self, value: T) {
+            if self.len == self.cap {
+                self.grow(self.len + 1);
+            }
+            unsafe { self.ptr.as_ptr().add(self.len).write(value) };
+            self.len += 1;
+        }
+
+        pub fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                None
+            } else {
+                self.len -= 1;
+                Some(unsafe { self.ptr.as_ptr().add(self.len).read() })
+            }
+        }
+
+        pub fn clear(&mut self) {
+            let elements: *mut [T] = &mut **self;
+            self.len = 0;
+            unsafe { ptr::drop_in_place(elements) };
+        }
+
+        // Moves the elements to a new mapping, wiping the old one
+        fn grow(&mut self, min_capacity: usize) {
+            let mut grown = Self::with_capacity(cmp::max(self.cap * 2, min_capacity));
+            unsafe { ptr::copy_nonoverlapping(self.ptr.as_ptr(), grown.ptr.as_ptr(), self.len) };
+            grown.len = self.len;
+            self.len = 0;
+            mem::swap(self, &mut grown);
+        }
+    }
+
+    impl<T> Drop for LockedVec<T> {
+        fn drop(&mut self) {
+            self.clear();
+        }
+    }
+
+    impl<T> Default for LockedVec<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Deref for LockedVec<T> {
+        type Target = [T];
+
+        fn deref(&self) -> &[T] {
+            unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T> DerefMut for LockedVec<T> {
+        fn deref_mut(&mut self) -> &mut [T] {
+            unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T: Clone> Clone for LockedVec<T> {
+        fn clone(&self) -> Self {
+            self.iter().cloned().collect()
+        }
+    }
+
+    impl<T> Extend<T> for LockedVec<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for value in iter {
+                self.push(value);
+            }
+        }
+    }
+
+    impl<T> core::iter::FromIterator<T> for LockedVec<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let iter = iter.into_iter();
+            let mut locked = Self::with_capacity(iter.size_hint().0);
+            locked.extend(iter);
+            locked
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a LockedVec<T> {
+        type Item = &'a T;
+        type IntoIter = slice::Iter<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for LockedVec<T> {
+        fn eq(&self, other: &Self) -> bool {
+            **self == **other
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for LockedVec<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Zeroize> Zeroize for LockedVec<T> {
+        fn zeroize(&mut self) {
+            self.iter_mut().for_each(Zeroize::zeroize);
+            self.clear();
+        }
+    }
+
+    #[cfg(feature = "fuzzing")]
+    impl<T: Arbitrary> Arbitrary for LockedVec<T> {
+        fn arbitrary(u: &mut Unstructured<'_>) -> arbitrary::Result<Self> {
+            u.arbitrary_iter()?.collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{lock_failures, page_size, LockedVec};
+
+        #[test]
+        fn push_and_grow_work() {
+            let mut locked = LockedVec::new();
+            for i in 0..2 * page_size() {
+                locked.push(i as u8);
+            }
+            assert_eq!(locked.len(), 2 * page_size());
+            assert!(locked.iter().enumerate().all(|(i, b)| *b == i as u8));
+            assert!(locked.clone() == locked);
+        }
+
+        #[test]
+        fn elements_end_at_guard_page() {
+            let locked: LockedVec<u8> = (0..10).collect();
+            let end = locked.ptr.as_ptr() as usize + locked.cap;
+            assert_eq!(end % page_size(), 0);
+        }
+
+        // Runs itself in a child process which can not lock any memory: its `RLIMIT_MEMLOCK` is zero
+        // and `CAP_IPC_LOCK`, which would let root ignore the limit, is dropped.
+        #[cfg(feature = "std")]
+        #[test]
+        fn lock_failures_are_reported() {
+            use std::os::unix::process::CommandExt;
+            use std::process::Command;
+
+            const CHILD: &str = "SHARKS_TEST_MEMLOCK_CHILD";
+            const CAP_IPC_LOCK: libc::c_ulong = 14;
+
+            if std::env::var_os(CHILD).is_some() {
+                let before = lock_failures();
+                let locked: LockedVec<u8> = (0..10).collect();
+                assert!(!locked.is_locked());
+                assert_eq!(&locked[..], &(0..10).collect::<alloc::vec::Vec<u8>>()[..]);
+                assert_eq!(lock_failures(), before + 1);
+                return;
+            }
+
+            let mut child = Command::new(std::env::current_exe().unwrap());
+            child
+                .args([
+                    "memory::locked::tests::lock_failures_are_reported",
+                    "--exact",
+                    "--test-threads=1",
+                ])
+                .env(CHILD, "1");
+            unsafe {
+                child.pre_exec(|| {
+                    let limit = libc::rlimit {
+                        rlim_cur: 0,
+                        rlim_max: 0,
+                    };
+                    if libc::setrlimit(libc::RLIMIT_MEMLOCK, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    // Fails without `CAP_SETPCAP`, in which case `CAP_IPC_LOCK` is not held either
+                    libc::prctl(libc::PR_CAPBSET_DROP, CAP_IPC_LOCK, 0, 0, 0);
+                    Ok(())
+                });
+            }
+            let output = child.output().unwrap();
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stdout)
+            );
+        }
+    }
+}
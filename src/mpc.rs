@@ -0,0 +1,373 @@
+//! Multi-party computation protocols over Shamir's shares.
+//!
+//! Shares are linear (see `Share::affine`), but multiplying two shares locally yields a share of
+//! the product on a polynomial of degree `2 * (k - 1)`. The [BGW](https://doi.org/10.1145/62212.62213)
+//! degree reduction step brings it back to degree `k - 1`: each party re-shares its local product,
+//! and then combines the sub-shares it receives with the Lagrange recombination vector.
+//! This needs at least `2 * k - 1` parties.
+//!
+//! The module also provides conversions between Shamir's shares and the additive shares of `AdditiveSharks`,
+//! which many MPC frameworks expect.
+//!
+//! Usage example:
+//! ```
+//! # use sharks::{ Sharks, Share };
+//! # use sharks::mpc::Simulator;
+//! # use rand_chacha::rand_core::SeedableRng;
+//! let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+//! // 5 parties hold 3-of-5 shares of secrets A and B
+//! let simulator = Simulator::new(3, 5).unwrap();
+//! let a: Vec<Share> = simulator.deal_rng(&[2, 4], &mut rng);
+//! let b: Vec<Share> = simulator.deal_rng(&[3, 5], &mut rng);
+//! // They run the multiplication protocol, obtaining 3-of-5 shares of A * B
+//! let product = simulator.multiply_rng(&a, &b, &mut rng).unwrap();
+//! let secret = Sharks(3).recover(&product[2..]).unwrap();
+//! assert_eq!(secret.expose_secret(), &[6, 20]);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use super::field::GF256;
+use super::math;
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+/// A message sent by party `from` to party `share.x` while re-sharing its local product.
+#[derive(Clone)]
+pub struct SubShare {
+    pub from: u8,
+    pub share: Share,
+}
+
+/// Multiplies locally two shares at the same `x`, obtaining a share of the product of their secrets
+/// on a polynomial of twice the degree. If the shares have different `x` or length an `Err` is returned.
+pub fn multiply(a: &Share, b: &Share) -> Result<Share, &'static str> {
+    if a.x != b.x {
+        Err("All shares must have the same x")
+    } else if a.y.len() != b.y.len() {
+        Err("All shares must have the same length")
+    } else {
+        Ok(Share {
+            x: a.x.clone(),
+            y: a.y
+                .iter()
+                .zip(b.y.iter())
+                .map(|(a, b)| a.clone() * b.clone())
+                .collect(),
+        })
+    }
+}
+
+/// Re-shares a local `product` with the given `threshold`, returning an `Iterator` along the
+/// sub-shares for the parties at `x = 1, 2..`.
+/// A random number generator has to be provided.
+pub fn reshare_rng<R: rand::Rng>(
+    product: &Share,
+    threshold: u8,
+    rng: &mut R,
+) -> impl Iterator<Item = SubShare> {
+    let from = product.x.0;
+    // The product is as sensitive as the shares it was computed from, and is wiped once dealt
+    let secret = Secret(product.y.iter().map(|y| y.0).collect());
+
+    Sharks(threshold)
+        .dealer_rng(secret.expose_secret(), rng)
+        .map(move |share| SubShare { from, share })
+}
+
+/// Combines the sub-shares received by a party, one from each party which re-shared its product,
+/// with the Lagrange recombination vector. The result is the party's share of the product on a
+/// polynomial of the re-sharing degree. The product of shares with the given `threshold` lies on a
+/// polynomial of degree `2 * (threshold - 1)`, so at least `2 * threshold - 1` sub-shares are needed.
+/// If there are less, or the sub-shares are addressed to different parties, come twice from the same
+/// party or have different lengths, an `Err` is returned.
+pub fn recombine(subshares: &[SubShare], threshold: u8) -> Result<Share, &'static str> {
+    if threshold == 0 || subshares.len() < 2 * threshold as usize - 1 {
+        return Err("At least 2 * threshold - 1 sub-shares are needed");
+    }
+    let xs: Vec<GF256> = subshares.iter().map(|s| GF256(s.from)).collect();
+    combine(subshares, math::lagrange_coefficients(&xs, GF256(0)))
+}
+
+/// Converts a Shamir share into an additive share, for the active set of parties at `active` x coordinates.
+/// The `y` is multiplied by the party's Lagrange coefficient, so that the additive shares of the active set
+/// add up to the secret, and can be recovered with `AdditiveSharks`.
+/// If `active` doesn't include the share's `x` or has duplicates, an `Err` is returned.
+///
+/// Example:
+/// ```
+/// # use sharks::{ AdditiveSharks, Sharks, Share };
+/// # use sharks::mpc::shamir_to_additive;
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let shares: Vec<Share> = Sharks(3).dealer_rng(&[1, 2], &mut rng).take(5).collect();
+/// // Parties at x = 1, 3 and 5 obtain additive shares of the secret
+/// let additive: Vec<Share> = [0, 2, 4]
+///     .iter()
+///     .map(|i| shamir_to_additive(&shares[*i], &[1, 3, 5]).unwrap())
+///     .collect();
+/// let sharks: AdditiveSharks = AdditiveSharks(3);
+/// assert_eq!(sharks.recover(&additive).unwrap().expose_secret(), &[1, 2]);
+/// ```
+pub fn shamir_to_additive(share: &Share, active: &[u8]) -> Result<Share, &'static str> {
+    let xs: Vec<GF256> = active.iter().map(|x| GF256(*x)).collect();
+    if has_duplicates(&xs) {
+        return Err("Active parties must be distinct");
+    }
+
+    match xs.iter().position(|x| *x == share.x) {
+        Some(i) => Ok(share * math::lagrange_coefficients(&xs, GF256(0))[i].0),
+        None => Err("Share must belong to an active party"),
+    }
+}
+
+/// Re-shares an additive share with the given `threshold`, returning an `Iterator` along the
+/// sub-shares for the parties at `x = 1, 2..`. Each party adds up the sub-shares received from every
+/// additive share holder with `combine_additive`, obtaining a Shamir share of the secret.
+/// A random number generator has to be provided.
+///
+/// Example:
+/// ```
+/// # use sharks::{ AdditiveSharks, Sharks, Share };
+/// # use sharks::mpc::{ additive_to_shamir_rng, combine_additive, SubShare };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let sharks: AdditiveSharks = AdditiveSharks(2);
+/// let additive: Vec<Share> = sharks.dealer_rng(&[1, 2], &mut rng).collect();
+/// // Each additive share holder deals 2-of-3 sub-shares
+/// let subshares: Vec<Vec<SubShare>> = additive
+///     .iter()
+///     .map(|s| additive_to_shamir_rng(s, 2, &mut rng).take(3).collect())
+///     .collect();
+/// // Each of the 3 parties adds up the sub-shares it received
+/// let shares: Vec<Share> = (0..3)
+///     .map(|j| combine_additive(&[subshares[0][j].clone(), subshares[1][j].clone()]).unwrap())
+///     .collect();
+/// assert_eq!(Sharks(2).recover(&shares[1..]).unwrap().expose_secret(), &[1, 2]);
+/// ```
+pub fn additive_to_shamir_rng<R: rand::Rng>(
+    additive: &Share,
+    threshold: u8,
+    rng: &mut R,
+) -> impl Iterator<Item = SubShare> {
+    reshare_rng(additive, threshold, rng)
+}
+
+/// Adds up the sub-shares received by a party, one from each additive share holder,
+/// obtaining the party's Shamir share of the secret.
+/// If the sub-shares are addressed to different parties, come twice from the same holder or have
+/// different lengths, an `Err` is returned.
+pub fn combine_additive(subshares: &[SubShare]) -> Result<Share, &'static str> {
+    combine(subshares, vec![GF256(1); subshares.len()])
+}
+
+// Returns whether there are repeated elements in `xs`.
+fn has_duplicates(xs: &[GF256]) -> bool {
+    (1..xs.len()).any(|i| xs[..i].contains(&xs[i]))
+}
+
+// Computes the linear combination of the sub-shares with the given coefficients.
+fn combine(subshares: &[SubShare], coefficients: Vec<GF256>) -> Result<Share, &'static str> {
+    let xs: Vec<GF256> = subshares.iter().map(|s| GF256(s.from)).collect();
+    if xs.is_empty() {
+        return Err("At least one sub-share is needed");
+    } else if has_duplicates(&xs) {
+        return Err("Sub-shares must come from distinct parties");
+    }
+
+    Share::affine(
+        subshares
+            .iter()
+            .zip(coefficients.iter())
+            .map(|(s, l)| (l.0, &s.share)),
+        &vec![0; subshares[0].share.y.len()],
+    )
+}
+
+/// An in-process simulator of the multiplication protocol among `parties` parties
+/// holding shares with the given `threshold`. Party `i` holds the shares at `x = i`.
+pub struct Simulator {
+    threshold: u8,
+    parties: u8,
+}
+
+impl Simulator {
+    /// Creates a new simulator. Returns an `Err` unless there are at least `2 * threshold - 1` parties,
+    /// which are needed to reduce the product degree.
+    pub fn new(threshold: u8, parties: u8) -> Result<Self, &'static str> {
+        if threshold == 0 || (parties as usize) < 2 * threshold as usize - 1 {
+            Err("At least 2 * threshold - 1 parties are needed")
+        } else {
+            Ok(Self { threshold, parties })
+        }
+    }
+
+    /// Deals one share of `secret` to each party.
+    /// A random number generator has to be provided.
+    pub fn deal_rng<R: rand::Rng>(&self, secret: &[u8], rng: &mut R) -> Vec<Share> {
+        Sharks(self.threshold)
+            .dealer_rng(secret, rng)
+            .take(self.parties as usize)
+            .collect()
+    }
+
+    /// Runs the multiplication protocol, where `a` and `b` hold one share of each party, in party order.
+    /// Returns the parties' shares of the product, on a polynomial of degree `threshold - 1`.
+    /// A random number generator has to be provided.
+    pub fn multiply_rng<R: rand::Rng>(
+        &self,
+        a: &[Share],
+        b: &[Share],
+        rng: &mut R,
+    ) -> Result<Vec<Share>, &'static str> {
+        if a.len() != self.parties as usize || b.len() != self.parties as usize {
+            return Err("Every party must hold one share of each secret");
+        }
+        if a.iter().enumerate().any(|(i, s)| s.x.0 as usize != i + 1) {
+            return Err("Shares must be given in party order");
+        }
+
+        let mut inboxes: Vec<Vec<SubShare>> = (0..self.parties).map(|_| Vec::new()).collect();
+        for (a, b) in a.iter().zip(b.iter()) {
+            let product = multiply(a, b)?;
+            for (inbox, subshare) in
+                inboxes
+                    .iter_mut()
+                    .zip(reshare_rng(&product, self.threshold, rng))
+            {
+                inbox.push(subshare);
+            }
+        }
+
+        inboxes
+            .iter()
+            .map(|inbox| recombine(inbox, self.threshold))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        additive_to_shamir_rng, combine_additive, multiply, recombine, reshare_rng,
+        shamir_to_additive, Simulator, SubShare,
+    };
+    use crate::field::GF256;
+    use crate::{AdditiveSharks, Share, Sharks};
+    use alloc::{vec, vec::Vec};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    #[test]
+    fn multiply_works() {
+        let a = Share {
+            x: GF256(1),
+            y: [2, 3].iter().copied().map(GF256).collect(),
+        };
+        let b = Share {
+            x: GF256(1),
+            y: [3, 3].iter().copied().map(GF256).collect(),
+        };
+        assert_eq!(multiply(&a, &b).unwrap().y[..], [GF256(6), GF256(5)]);
+        let c = Share {
+            x: GF256(2),
+            y: [3, 3].iter().copied().map(GF256).collect(),
+        };
+        assert!(multiply(&a, &c).is_err());
+    }
+
+    #[test]
+    fn recombine_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<Share> = Sharks(2).dealer_rng(&[7], &mut rng).take(3).collect();
+        let inboxes: Vec<Vec<SubShare>> = shares
+            .iter()
+            .map(|s| reshare_rng(s, 2, &mut rng).take(2).collect())
+            .collect();
+        let received: Vec<SubShare> = inboxes.iter().map(|i| i[1].clone()).collect();
+        let share = recombine(&received, 2).unwrap();
+        assert_eq!(share.x, GF256(2));
+
+        // The product degree can not be reduced from less than 2 * threshold - 1 sub-shares
+        assert!(recombine(&received[..2], 2).is_err());
+        assert!(recombine(&received, 0).is_err());
+        let duplicated = vec![
+            received[0].clone(),
+            received[0].clone(),
+            received[1].clone(),
+        ];
+        assert!(recombine(&duplicated, 2).is_err());
+    }
+
+    #[test]
+    fn shamir_to_additive_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<Share> = Sharks(4).dealer_rng(&[1, 2, 3], &mut rng).take(9).collect();
+        let active = [9, 2, 5, 7];
+        let additive: Vec<Share> = active
+            .iter()
+            .map(|x| shamir_to_additive(&shares[*x as usize - 1], &active).unwrap())
+            .collect();
+        assert_eq!(additive[0].x, GF256(9));
+        let sharks: AdditiveSharks = AdditiveSharks(4);
+        assert_eq!(
+            sharks.recover(&additive).unwrap().expose_secret(),
+            &[1, 2, 3]
+        );
+        assert!(shamir_to_additive(&shares[0], &active).is_err());
+        assert!(shamir_to_additive(&shares[1], &[2, 2, 5, 7]).is_err());
+    }
+
+    #[test]
+    fn additive_to_shamir_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let sharks: AdditiveSharks = AdditiveSharks(5);
+        let additive: Vec<Share> = sharks.dealer_rng(&[1, 2, 3], &mut rng).collect();
+        let dealt: Vec<Vec<SubShare>> = additive
+            .iter()
+            .map(|s| additive_to_shamir_rng(s, 3, &mut rng).take(4).collect())
+            .collect();
+        let shares: Vec<Share> = (0..4)
+            .map(|j| {
+                let received: Vec<SubShare> = dealt.iter().map(|d| d[j].clone()).collect();
+                combine_additive(&received).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            Sharks(3).recover(&shares[1..]).unwrap().expose_secret(),
+            &[1, 2, 3]
+        );
+        assert!(combine_additive(&[dealt[0][0].clone(), dealt[0][0].clone()]).is_err());
+        assert!(combine_additive(&[dealt[0][0].clone(), dealt[1][1].clone()]).is_err());
+    }
+
+    #[test]
+    fn simulator_checks_parties() {
+        assert!(Simulator::new(3, 4).is_err());
+        assert!(Simulator::new(0, 4).is_err());
+        assert!(Simulator::new(128, 255).is_ok());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let simulator = Simulator::new(10, 30).unwrap();
+        let a: Vec<u8> = (0..=255).collect();
+        let b: Vec<u8> = (0..=255).rev().collect();
+        let a_shares = simulator.deal_rng(&a, &mut rng);
+        let b_shares = simulator.deal_rng(&b, &mut rng);
+        let product = simulator
+            .multiply_rng(&a_shares, &b_shares, &mut rng)
+            .unwrap();
+        let expected: Vec<u8> = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| (GF256::<0x11d>(*a) * GF256(*b)).0)
+            .collect();
+        assert_eq!(
+            Sharks(10).recover(&product[20..]).unwrap().expose_secret(),
+            expected
+        );
+        assert!(Sharks(10).recover(&product[21..]).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Python bindings, enabled with the `python` feature, built as the `sharks` extension module by `maturin`.
+//!
+//! Shares are `sharks.Share` objects, serialized with `bytes(share)` as their `x` byte followed by their `y` bytes,
+//! and deserialized with `Share.from_bytes`. Errors are raised as subclasses of `sharks.SharksError`,
+//! mirroring the codes of the C API. Secrets handed over to Python as `bytes` can not be wiped from memory.
+//!
+//! ```python
+//! import sharks
+//!
+//! shares = sharks.split(b"recovery key", 3, 5)
+//! secret = sharks.combine(shares[1:4], 3)
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use super::share::Share;
+use super::{Error, Sharks};
+
+create_exception!(
+    sharks,
+    SharksError,
+    PyException,
+    "Base class of the errors raised by sharks."
+);
+create_exception!(
+    sharks,
+    InvalidArgument,
+    SharksError,
+    "An argument was out of range, such as an empty secret or less shares than the threshold."
+);
+create_exception!(
+    sharks,
+    NotEnoughShares,
+    SharksError,
+    "There were less distinct shares than the threshold."
+);
+create_exception!(
+    sharks,
+    InconsistentShares,
+    SharksError,
+    "The shares were inconsistent with each other, such as having different lengths."
+);
+create_exception!(
+    sharks,
+    InvalidShare,
+    SharksError,
+    "The bytes could not be deserialized into a share."
+);
+
+// Raises recovery failures as the matching exception
+fn raise(error: Error) -> PyErr {
+    match error {
+        Error::NotEnoughShares => NotEnoughShares::new_err(<&str>::from(error)),
+        Error::TooManyShares
+        | Error::InconsistentShares
+        | Error::ConflictingShares
+        | Error::NotOnPolynomial => InconsistentShares::new_err(<&str>::from(error)),
+    }
+}
+
+/// A share of a secret, which can be serialized with `bytes(share)`.
+#[pyclass(name = "Share", module = "sharks", frozen)]
+pub struct PyShare(Share);
+
+#[pymethods]
+impl PyShare {
+    /// Deserializes a share from its `x` byte followed by its `y` bytes.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Share::try_from(data)
+            .map(PyShare)
+            .map_err(InvalidShare::new_err)
+    }
+
+    /// The x coordinate of the share, which identifies it among the others.
+    #[getter]
+    fn x(&self) -> u8 {
+        self.0.x.0
+    }
+
+    /// Serializes the share as its `x` byte followed by its `y` bytes.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &Vec::from(&self.0))
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        self.to_bytes(py)
+    }
+
+    /// Length of the secret the share belongs to.
+    fn __len__(&self) -> usize {
+        self.0.y.len()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0.x == other.0.x && self.0.y[..] == other.0.y[..]
+    }
+
+    // Does not show the `y` bytes, as the share is sensitive
+    fn __repr__(&self) -> String {
+        format!("Share(x={}, len={})", self.0.x.0, self.0.y.len())
+    }
+}
+
+/// Splits `secret` into `n` shares, any `k` of which recover it.
+#[pyfunction]
+fn split(secret: &[u8], k: u8, n: u8) -> PyResult<Vec<PyShare>> {
+    if k == 0 || n < k {
+        return Err(InvalidArgument::new_err(
+            "The number of shares must be at least the threshold, which can not be zero",
+        ));
+    }
+    if secret.is_empty() {
+        return Err(InvalidArgument::new_err("Secret can not be empty"));
+    }
+
+    Ok(Sharks(k)
+        .dealer(secret)
+        .take(n as usize)
+        .map(PyShare)
+        .collect())
+}
+
+/// Recovers the secret from `shares`, checking that there are at least `k` distinct ones.
+/// The threshold `k` is required, as recovering with a lower one than the shares were dealt with yields a wrong secret.
+#[pyfunction]
+fn combine<'py>(
+    py: Python<'py>,
+    shares: Vec<PyRef<'py, PyShare>>,
+    k: u8,
+) -> PyResult<Bound<'py, PyBytes>> {
+    if k == 0 {
+        return Err(InvalidArgument::new_err("Threshold can not be zero"));
+    }
+
+    let secret = Sharks(k)
+        .recover(shares.iter().map(|share| &share.0))
+        .map_err(raise)?;
+    Ok(PyBytes::new(py, secret.expose_secret()))
+}
+
+/// Shamir's Secret Sharing, splitting secrets into shares of which a threshold recovers them.
+#[pymodule]
+fn sharks(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyShare>()?;
+    m.add_function(wrap_pyfunction!(split, m)?)?;
+    m.add_function(wrap_pyfunction!(combine, m)?)?;
+    m.add("SharksError", py.get_type::<SharksError>())?;
+    m.add("InvalidArgument", py.get_type::<InvalidArgument>())?;
+    m.add("NotEnoughShares", py.get_type::<NotEnoughShares>())?;
+    m.add("InconsistentShares", py.get_type::<InconsistentShares>())?;
+    m.add("InvalidShare", py.get_type::<InvalidShare>())?;
+    Ok(())
+}
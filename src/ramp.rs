@@ -0,0 +1,289 @@
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+use super::field::GF256;
+use super::math;
+use super::memory::SecretVec;
+use super::secret::Secret;
+use super::share::Share;
+
+/// Struct which implements packed (ramp) secret sharing over a 256 bits Galois Field.
+/// It has two parameters: the `privacy` threshold and the recovery `threshold`.
+///
+/// Each polynomial embeds `threshold - privacy` secret bytes at distinct points, instead of a single one,
+/// so shares are that many times smaller than with `Sharks`. In exchange, the scheme has a gap:
+/// - Any `privacy` shares or less reveal nothing about the secret.
+/// - Any `threshold` shares recover the whole secret.
+/// - In between, each additional share leaks some information about the secret.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ RampSharks, Share };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // Nothing is learned from 2 shares, and the secret is recovered from 6
+/// let sharks = RampSharks::new(2, 6).unwrap();
+/// // Obtain an iterator over the shares for secret [1, 2, 3, 4, 5, 6, 7, 8]
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let dealer = sharks.dealer_rng(&[1, 2, 3, 4, 5, 6, 7, 8], &mut rng);
+/// // Get 10 shares, each one holding 3 bytes instead of 8
+/// let shares: Vec<Share> = dealer.take(10).collect();
+/// assert_eq!(Vec::from(&shares[0]).len(), 1 + 3);
+/// // Recover the original secret!
+/// let secret = sharks.recover(&shares[..6]).unwrap();
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+/// ```
+pub struct RampSharks {
+    privacy: u8,
+    threshold: u8,
+}
+
+impl RampSharks {
+    /// Creates a new ramp scheme where any `privacy` shares reveal nothing about the secret,
+    /// and any `threshold` shares recover it. Returns an `Err` unless `0 < privacy < threshold`,
+    /// and there are at least `threshold` share points left after reserving the secret ones.
+    pub fn new(privacy: u8, threshold: u8) -> Result<Self, &'static str> {
+        if privacy == 0 || privacy >= threshold {
+            Err("Privacy threshold must be positive and lower than the recovery threshold")
+        } else if 2 * threshold as usize - privacy as usize > 256 {
+            Err("Not enough share points for the given thresholds")
+        } else {
+            Ok(Self { privacy, threshold })
+        }
+    }
+
+    /// Maximum number of shares which reveal nothing about the secret.
+    pub fn privacy(&self) -> u8 {
+        self.privacy
+    }
+
+    /// Minimum number of shares needed to recover the secret.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Number of secret bytes packed in each polynomial.
+    pub fn packing(&self) -> u8 {
+        self.threshold - self.privacy
+    }
+
+    // Secret bytes are embedded at `x = 0, 255, 254..`, so shares are evaluated starting at `x = 1`
+    // until the first of those points.
+    fn secret_points(&self) -> Vec<GF256> {
+        (0..self.packing())
+            .map(|j| GF256(0u8.wrapping_sub(j)))
+            .collect()
+    }
+
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares.
+    /// The maximum number of shares that can be generated is `256 - packing`.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ RampSharks, Share };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = RampSharks::new(1, 3).unwrap();
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(&[1, 2], &mut rng);
+    /// // Get 3 shares
+    /// let shares: Vec<Share> = dealer.take(3).collect();
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> impl Iterator<Item = Share> {
+        // Each polynomial is defined by its values at the secret points and random values
+        // at the first `privacy` shares points.
+        let mut xs = self.secret_points();
+        xs.extend((1..=self.privacy).map(GF256));
+
+        // The secret is padded as by `math::pad`, but on the fly, so that its bytes are only copied into
+        // the values buffer, which is wiped on drop along with the random values.
+        let (packing, threshold) = (self.packing() as usize, self.threshold as usize);
+        let len = (secret.len() / packing + 1) * packing;
+        let padded = secret
+            .iter()
+            .cloned()
+            .chain(core::iter::once(0x80))
+            .chain(core::iter::repeat(0))
+            .take(len);
+        let mut values: SecretVec<GF256> = SecretVec::with_capacity(len / packing * threshold);
+        for (i, b) in padded.enumerate() {
+            values.push(GF256(b));
+            if i % packing == packing - 1 {
+                for _ in 0..self.privacy {
+                    values.push(GF256(rng.gen()));
+                }
+            }
+        }
+
+        (1..=0u8.wrapping_sub(self.packing())).map(move |x| {
+            let coefficients = math::lagrange_coefficients(&xs, GF256(x));
+            Share {
+                x: GF256(x),
+                y: values
+                    .chunks(threshold)
+                    .map(|v| {
+                        v.iter()
+                            .zip(coefficients.iter())
+                            .map(|(v_i, l_i)| v_i.clone() * l_i.clone())
+                            .sum()
+                    })
+                    .collect(),
+            }
+        })
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares.
+    /// The maximum number of shares that can be generated is `256 - packing`.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ RampSharks, Share };
+    /// # let sharks = RampSharks::new(1, 3).unwrap();
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let dealer = sharks.dealer(&[1, 2]);
+    /// // Get 3 shares
+    /// let shares: Vec<Share> = dealer.take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> impl Iterator<Item = Share> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret.
+    /// If the number of distinct shares is less than the recovery threshold an `Err` is returned,
+    /// otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ RampSharks, Share };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = RampSharks::new(1, 3).unwrap();
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<Share> = sharks.dealer_rng(&[1], &mut rng).take(3).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove shares for demonstration purposes
+    /// shares.pop();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough shares to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a Share>,
+        T::IntoIter: Iterator<Item = &'a Share>,
+    {
+        let mut share_length: Option<usize> = None;
+        let mut keys: HashSet<u8> = HashSet::new();
+        let mut values: Vec<&Share> = Vec::new();
+
+        for share in shares.into_iter() {
+            if share_length.is_none() {
+                share_length = Some(share.y.len());
+            }
+
+            if Some(share.y.len()) != share_length {
+                return Err("All shares must have the same length");
+            } else if share.x.0 == 0 || share.x.0 > 0u8.wrapping_sub(self.packing()) {
+                return Err("Share x coordinate is reserved for the secret");
+            } else if keys.insert(share.x.0) && values.len() < self.threshold as usize {
+                values.push(share);
+            }
+        }
+
+        if values.len() < self.threshold as usize {
+            return Err("Not enough shares to recover original secret");
+        }
+
+        let chunks: Vec<Secret> = self
+            .secret_points()
+            .into_iter()
+            .map(|e| Secret(math::interpolate_at(&values, e)))
+            .collect();
+        // The padded secret is wiped on drop, even if its padding is invalid
+        let mut padded = Secret(SecretVec::with_capacity(values[0].y.len() * chunks.len()));
+        for g in 0..values[0].y.len() {
+            padded.0.extend(chunks.iter().map(|c| c.expose_secret()[g]));
+        }
+
+        let len = math::unpadded_len(padded.expose_secret())?;
+        Ok(Secret::from(padded.expose_secret()[..len].to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RampSharks, Share};
+    use alloc::vec::Vec;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    #[test]
+    fn new_checks_thresholds() {
+        assert!(RampSharks::new(0, 3).is_err());
+        assert!(RampSharks::new(3, 3).is_err());
+        assert!(RampSharks::new(50, 160).is_err());
+        assert_eq!(RampSharks::new(2, 3).unwrap().packing(), 1);
+    }
+
+    #[test]
+    fn test_insufficient_shares_err() {
+        let sharks = RampSharks::new(3, 8).unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<Share> = sharks.dealer_rng(&[1, 2, 3], &mut rng).take(7).collect();
+        assert!(sharks.recover(&shares).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_shares_err() {
+        let sharks = RampSharks::new(1, 3).unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let mut shares: Vec<Share> = sharks.dealer_rng(&[1, 2, 3], &mut rng).take(3).collect();
+        shares[1] = shares[0].clone();
+        assert!(sharks.recover(&shares).is_err());
+    }
+
+    #[test]
+    fn test_invalid_padding_err() {
+        let sharks = RampSharks::new(4, 5).unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        // Scaling the shares scales the padding marker too
+        let shares: Vec<Share> = sharks
+            .dealer_rng(&[1, 2, 3], &mut rng)
+            .take(5)
+            .map(|s| &s * 2)
+            .collect();
+        assert!(sharks.recover(&shares).is_err());
+    }
+
+    #[test]
+    fn test_single_packing_is_shamir() {
+        let sharks = RampSharks::new(4, 5).unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<Share> = sharks.dealer_rng(&[1, 2, 3], &mut rng).take(255).collect();
+        assert_eq!(shares.len(), 255);
+        assert_eq!(
+            crate::Sharks(5).recover(&shares).unwrap().expose_secret(),
+            &[1, 2, 3, 0x80]
+        );
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let sharks = RampSharks::new(50, 150).unwrap();
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let secret: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        let shares: Vec<Share> = sharks.dealer_rng(&secret, &mut rng).take(156).collect();
+        assert_eq!(shares[0].y.len(), 11);
+        assert_eq!(
+            sharks.recover(shares.iter().rev()).unwrap().expose_secret(),
+            secret
+        );
+    }
+}
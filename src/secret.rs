@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::memory::SecretVec;
+
+/// A recovered secret. Its bytes are only exposed through the explicit `expose_secret` accessor,
+/// and are redacted from the `Debug` output. With the `zeroize_memory` feature they are wiped on drop.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ Sharks, Share };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # let sharks = Sharks(2);
+/// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// # let shares: Vec<Share> = sharks.dealer_rng(&[1, 2, 3], &mut rng).take(2).collect();
+/// let secret = sharks.recover(&shares).unwrap();
+/// assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3]);
+/// ```
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct Secret(pub(crate) SecretVec<u8>);
+
+impl Secret {
+    /// Exposes the secret bytes.
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Takes ownership of a byte vector, which will be wiped on drop along with the `Secret`.
+/// With the `mlock` feature the bytes are moved to locked memory, and the vector is wiped right away.
+impl From<Vec<u8>> for Secret {
+    #[cfg(not(all(feature = "mlock", target_os = "linux")))]
+    fn from(bytes: Vec<u8>) -> Secret {
+        Secret(bytes)
+    }
+
+    #[cfg(all(feature = "mlock", target_os = "linux"))]
+    fn from(mut bytes: Vec<u8>) -> Secret {
+        let secret = Secret(bytes.iter().cloned().collect());
+        bytes.zeroize();
+        secret
+    }
+}
+
+impl core::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Secret;
+    use alloc::{format, vec};
+
+    #[test]
+    fn debug_is_redacted() {
+        let secret = Secret::from(vec![1, 2, 3]);
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+    }
+
+    #[test]
+    fn expose_secret_works() {
+        let secret = Secret::from(vec![1, 2, 3]);
+        assert_eq!(secret.expose_secret(), &[1, 2, 3]);
+    }
+}
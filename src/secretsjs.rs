@@ -0,0 +1,351 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+
+use super::field::GF256;
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+// secrets.js prints the bits of its field in base 36, followed by the share id in as many hex digits as `2**bits - 1`
+const BITS: char = '8';
+const MAX_PAD_LENGTH: u16 = 1024;
+
+/// Struct which implements the share format of the [secrets.js](https://github.com/grempe/secrets.js) JavaScript
+/// library, so that shares can be exchanged with its `share` and `combine` functions.
+///
+/// secrets.js works over the same 256 bits Galois Field as `Sharks`, defined by the 0x11d polynomial, which is
+/// its default configuration of 8 bits per share word (`secrets.init(8)`). Other field sizes are not supported.
+/// Before sharing, it prepends a `1` marker bit to the secret and left pads it with zeros to a multiple of
+/// `pad_length` bits, 128 by default, so that shares do not reveal the exact length of the secret.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ SecretsJsSharks, SecretsJsShare };
+/// # use core::str::FromStr;
+/// // Combine shares obtained with `secrets.share(secrets.str2hex(..), 5, 3)`
+/// let sharks = SecretsJsSharks { threshold: 3, pad_length: 128 };
+/// let shares: Vec<SecretsJsShare> = [
+///     "805b2155a9c84adcd0fbbd0fd4d5439a2fe",
+///     "8029e52f1b51cb0b7b300996b31803528eb",
+///     "80387f70c666fb540825d55e3d01bee989e",
+/// ]
+/// .iter()
+/// .map(|s| SecretsJsShare::from_str(s).unwrap())
+/// .collect();
+/// let secret = sharks.recover(&shares).unwrap();
+/// assert_eq!(secret.expose_secret(), b"secrets.js");
+/// ```
+pub struct SecretsJsSharks {
+    pub threshold: u8,
+    pub pad_length: u16,
+}
+
+/// A share of `SecretsJsSharks`, which is printed and parsed in the secrets.js hex format: the bits of the field
+/// in base 36, the share id as 2 hex digits and the hex encoded `y` of the padded secret.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct SecretsJsShare {
+    pub x: u8,
+    pub y: Vec<u8>,
+}
+
+// `y` is not printed, only its length
+impl fmt::Debug for SecretsJsShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretsJsShare")
+            .field("x", &self.x)
+            .field("len", &self.y.len())
+            .finish()
+    }
+}
+
+impl SecretsJsSharks {
+    // Prepends the marker byte to the secret and left pads it with zeros, as secrets.js does bit by bit.
+    fn pad(&self, secret: &[u8]) -> Result<Secret, &'static str> {
+        if self.pad_length > MAX_PAD_LENGTH {
+            return Err("Padding length must be at most 1024 bits");
+        }
+
+        let bits = 8 * secret.len() + 1;
+        let padded_bits = match self.pad_length as usize {
+            0 | 1 => bits,
+            pad_length => bits.div_ceil(pad_length) * pad_length,
+        };
+
+        let mut padded = vec![0; padded_bits.div_ceil(8) - secret.len() - 1];
+        padded.push(1);
+        padded.extend_from_slice(secret);
+        Ok(Secret::from(padded))
+    }
+
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares with ids `1, 2, ..`, as secrets.js does.
+    /// The maximum number of shares that can be generated is 255.
+    /// If the threshold is lower than 2 or the padding length is over 1024 bits, an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SecretsJsSharks, SecretsJsShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = SecretsJsSharks { threshold: 3, pad_length: 128 };
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(&[1, 2], &mut rng).unwrap();
+    /// // Get 3 shares, printed as `secrets.combine` expects them
+    /// let shares: Vec<String> = dealer.take(3).map(|s| s.to_string()).collect();
+    /// assert_eq!(shares[0].len(), 3 + 32);
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = SecretsJsShare>, &'static str> {
+        if self.threshold < 2 {
+            return Err("Threshold must be at least 2");
+        }
+
+        let padded = self.pad(secret)?;
+        Ok(Sharks(self.threshold)
+            .dealer_rng(padded.expose_secret(), rng)
+            .map(|share| SecretsJsShare::from(&share)))
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares with ids `1, 2, ..`, as secrets.js does.
+    /// The maximum number of shares that can be generated is 255.
+    /// If the threshold is lower than 2 or the padding length is over 1024 bits, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SecretsJsSharks, SecretsJsShare };
+    /// # let sharks = SecretsJsSharks { threshold: 3, pad_length: 128 };
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let dealer = sharks.dealer(&[1, 2]).unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<SecretsJsShare> = dealer.take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(
+        &self,
+        secret: &[u8],
+    ) -> Result<impl Iterator<Item = SecretsJsShare>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret and removes its padding.
+    /// If the number of distinct shares is less than the minimum threshold, their lengths differ or the padding
+    /// is not valid, an `Err` is returned, otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SecretsJsSharks, SecretsJsShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = SecretsJsSharks { threshold: 3, pad_length: 128 };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<SecretsJsShare> = sharks.dealer_rng(&[1], &mut rng).unwrap().take(3).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove shares for demonstration purposes
+    /// shares.clear();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough shares to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a SecretsJsShare>,
+        T::IntoIter: Iterator<Item = &'a SecretsJsShare>,
+    {
+        let shares: Vec<Share> = shares.into_iter().map(Share::from).collect();
+        let padded = Sharks(self.threshold).recover(&shares)?;
+
+        // The secret follows the leading zeros and the marker byte
+        match padded.expose_secret().iter().position(|b| *b != 0) {
+            Some(marker) if padded.expose_secret()[marker] == 1 => {
+                Ok(Secret::from(padded.expose_secret()[marker + 1..].to_vec()))
+            }
+            _ => Err("Invalid secret padding"),
+        }
+    }
+}
+
+/// Obtains a `Share` instance from a `SecretsJsShare`, as both libraries share secrets over the same field
+impl From<&SecretsJsShare> for Share {
+    fn from(s: &SecretsJsShare) -> Share {
+        Share {
+            x: GF256(s.x),
+            y: s.y.iter().map(|p| GF256(*p)).collect(),
+        }
+    }
+}
+
+/// Obtains a `SecretsJsShare` instance from a `Share` of a padded secret
+impl From<&Share> for SecretsJsShare {
+    fn from(s: &Share) -> SecretsJsShare {
+        SecretsJsShare {
+            x: s.x.0,
+            y: s.y.iter().map(|p| p.0).collect(),
+        }
+    }
+}
+
+/// Prints the share as secrets.js does, as the field bits, the share id and the hex encoded `y`
+impl fmt::Display for SecretsJsShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:02x}", BITS, self.x)?;
+        for b in &self.y {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a share in the secrets.js hex format
+impl FromStr for SecretsJsShare {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<SecretsJsShare, Self::Err> {
+        let s = s.trim();
+        if !s.starts_with(BITS) {
+            return Err("Only shares of 8 bits secrets.js fields are supported");
+        }
+
+        let x = s
+            .get(1..3)
+            .and_then(|x| u8::from_str_radix(x, 16).ok())
+            .filter(|x| *x > 0)
+            .ok_or("Invalid share id")?;
+
+        let hex = &s[3..];
+        if hex.is_empty() || hex.len() % 2 == 1 {
+            return Err("Share data must be a non-empty, even length hex string");
+        }
+        let y = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("-"), 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "Share is not hex encoded")?;
+
+        Ok(SecretsJsShare { x, y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SecretsJsShare, SecretsJsSharks};
+    use alloc::string::ToString;
+    use alloc::{format, vec::Vec};
+    use core::str::FromStr;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn parse(shares: &[&str]) -> Vec<SecretsJsShare> {
+        shares
+            .iter()
+            .map(|s| SecretsJsShare::from_str(s).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn recover_secrets_js_shares_works() {
+        // secrets.share(secrets.str2hex(..), 5, 3, 0)
+        let shares = parse(&[
+            "8011dfdb8af57c55effae9db460c189e00fa148c3a41b6637c2dbc47670d00f9aef39c784f3076d92112aa9eac1efbed118",
+            "802e03a7896a28b28d5a1c05638719d6eb1fdc33c87fdfd566a60ebeb69a956effebe30889568d9a1f6148e6c65877e23f5",
+            "803fdc7c039f54e762a0f5c8378dc7be0d939f9df5083f813cdcf0fee71182b1075a79575461bdc56c74942e4841ca59380",
+            "8049227a442697b3a6ff829354a231a6ace7c3e2387c528b8161dd32cae0a65d5338bd2de6f83c12c7bf20b72f8c8a91995",
+            "8058fda1ced3ebe649056b5e00a8efce4a6b804c050bb2dfdb1b23729b6bb182ab8927723bcf0c4db4aafc7fa195372a9e0",
+        ]);
+        let sharks = SecretsJsSharks {
+            threshold: 3,
+            pad_length: 0,
+        };
+        for window in shares.windows(3) {
+            let secret = sharks.recover(window).unwrap();
+            assert_eq!(
+                secret.expose_secret(),
+                b"a longer secret shared by the web team"
+            );
+        }
+        assert!(sharks.recover(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn share_hex_format_works() {
+        let share = SecretsJsShare::from_str("80aff01").unwrap();
+        assert_eq!(share.x, 0x0a);
+        assert_eq!(share.y, [0xff, 0x01]);
+        assert_eq!(share.to_string(), "80aff01");
+        assert!(SecretsJsShare::from_str("90aff01").is_err());
+        assert!(SecretsJsShare::from_str("800ff01").is_err());
+        assert!(SecretsJsShare::from_str("80aff0").is_err());
+        assert!(SecretsJsShare::from_str("80a").is_err());
+        assert!(SecretsJsShare::from_str("80agg01").is_err());
+    }
+
+    #[test]
+    fn debug_hides_y() {
+        let share = SecretsJsShare::from_str("80aff01").unwrap();
+        assert_eq!(format!("{:?}", share), "SecretsJsShare { x: 10, len: 2 }");
+    }
+
+    #[test]
+    fn padding_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        for (pad_length, secret_len, share_len) in [
+            (128, 10, 16),
+            (128, 15, 16),
+            (128, 16, 32),
+            (0, 3, 4),
+            (20, 3, 5),
+        ]
+        .iter()
+        {
+            let sharks = SecretsJsSharks {
+                threshold: 2,
+                pad_length: *pad_length,
+            };
+            let secret: Vec<u8> = (0..*secret_len).collect();
+            let shares: Vec<SecretsJsShare> = sharks
+                .dealer_rng(&secret, &mut rng)
+                .unwrap()
+                .take(2)
+                .collect();
+            assert_eq!(shares[0].y.len(), *share_len);
+            assert_eq!(
+                sharks.recover(&shares).unwrap().expose_secret(),
+                secret.as_slice()
+            );
+        }
+
+        let sharks = SecretsJsSharks {
+            threshold: 2,
+            pad_length: 1025,
+        };
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let sharks = SecretsJsSharks {
+            threshold: 3,
+            pad_length: 128,
+        };
+        let shares: Vec<SecretsJsShare> = sharks
+            .dealer_rng(&[0, 0, 1, 2], &mut rng)
+            .unwrap()
+            .take(5)
+            .map(|s| SecretsJsShare::from_str(&s.to_string()).unwrap())
+            .collect();
+        assert_eq!(shares[4].x, 5);
+        let secret = sharks.recover(&shares[1..4]).unwrap();
+        assert_eq!(secret.expose_secret(), &[0, 0, 1, 2]);
+    }
+}
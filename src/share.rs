@@ -1,6 +1,8 @@
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Mul};
 
 use super::field::GF256;
+use super::memory::SecretVec;
 
 #[cfg(feature = "fuzzing")]
 use arbitrary::Arbitrary;
@@ -38,7 +40,94 @@ use zeroize::Zeroize;
 #[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
 pub struct Share {
     pub x: GF256,
-    pub y: Vec<GF256>,
+    pub y: SecretVec<GF256>,
+}
+
+/// Shamir's shares are linear, so shares can be combined locally by their holders, without reconstructing
+/// anything. Adding shares of secrets `A` and `B` at the same `x` yields a share of `A + B`, and scaling a share
+/// of `A` by a public constant `c` yields a share of `c * A`. Operations are performed byte by byte over the
+/// 256 bits Galois Field, where addition is a XOR.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ Sharks, Share };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// let sharks = Sharks(3);
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let a: Vec<Share> = sharks.dealer_rng(&[1, 2], &mut rng).take(3).collect();
+/// let b: Vec<Share> = sharks.dealer_rng(&[4, 8], &mut rng).take(3).collect();
+/// // Each holder adds its shares of A and B
+/// let sum: Vec<Share> = a.iter().zip(b.iter()).map(|(a, b)| a + b).collect();
+/// assert_eq!(sharks.recover(&sum).unwrap().expose_secret(), &[1 ^ 4, 2 ^ 8]);
+/// // Or computes any affine combination of them, such as 1 * A + 1 * B + [16, 16]
+/// let affine: Vec<Share> = a
+///     .iter()
+///     .zip(b.iter())
+///     .map(|(a, b)| Share::affine(vec![(1, a), (1, b)], &[16, 16]).unwrap())
+///     .collect();
+/// assert_eq!(sharks.recover(&affine).unwrap().expose_secret(), &[1 ^ 4 ^ 16, 2 ^ 8 ^ 16]);
+/// ```
+impl Share {
+    /// Adds two shares at the same `x`, obtaining a share of the sum of their secrets.
+    /// If the shares have different `x` or length an `Err` is returned.
+    pub fn checked_add(&self, other: &Share) -> Result<Share, &'static str> {
+        Share::affine(vec![(1, self), (1, other)], &vec![0; self.y.len()])
+    }
+
+    /// Computes the affine combination `c_0 * s_0 + c_1 * s_1 + .. + constant` of shares at the same `x`,
+    /// where the `c_i` coefficients and the `constant` bytes are public.
+    /// The result is a share of the same combination of the original secrets.
+    /// If there are no shares, or they have different `x` or length, an `Err` is returned.
+    pub fn affine<'a, T>(terms: T, constant: &[u8]) -> Result<Share, &'static str>
+    where
+        T: IntoIterator<Item = (u8, &'a Share)>,
+    {
+        let mut x: Option<GF256> = None;
+        let mut y: SecretVec<GF256> = constant.iter().map(|c| GF256(*c)).collect();
+
+        for (c, share) in terms.into_iter() {
+            if x.is_none() {
+                x = Some(share.x.clone());
+            }
+
+            if Some(share.x.clone()) != x {
+                return Err("All shares must have the same x");
+            } else if share.y.len() != y.len() {
+                return Err("All shares must have the same length");
+            } else {
+                for (acc, s) in y.iter_mut().zip(share.y.iter()) {
+                    *acc = acc.clone() + GF256(c) * s.clone();
+                }
+            }
+        }
+
+        match x {
+            Some(x) => Ok(Share { x, y }),
+            None => Err("At least one share is needed"),
+        }
+    }
+}
+
+/// Adds two shares at the same `x`. Panics if they have different `x` or length, see `Share::checked_add`.
+impl Add for &Share {
+    type Output = Share;
+
+    fn add(self, other: &Share) -> Share {
+        self.checked_add(other)
+            .expect("Shares must have the same x and length")
+    }
+}
+
+/// Scales a share by a public constant.
+impl Mul<u8> for &Share {
+    type Output = Share;
+
+    fn mul(self, c: u8) -> Share {
+        Share {
+            x: self.x.clone(),
+            y: self.y.iter().map(|s| GF256(c) * s.clone()).collect(),
+        }
+    }
 }
 
 /// Obtains a byte vector from a `Share` instance
@@ -72,11 +161,69 @@ mod tests {
     use alloc::{vec, vec::Vec};
     use core::convert::TryFrom;
 
+    #[test]
+    fn add_works() {
+        let a = Share {
+            x: GF256(1),
+            y: [2, 3].iter().copied().map(GF256).collect(),
+        };
+        let b = Share {
+            x: GF256(1),
+            y: [6, 3].iter().copied().map(GF256).collect(),
+        };
+        assert_eq!((&a + &b).y[..], [GF256(4), GF256(0)]);
+        assert_eq!((&a + &b).x, GF256(1));
+    }
+
+    #[test]
+    fn checked_add_err() {
+        let a = Share {
+            x: GF256(1),
+            y: [2, 3].iter().copied().map(GF256).collect(),
+        };
+        let b = Share {
+            x: GF256(2),
+            y: [6, 3].iter().copied().map(GF256).collect(),
+        };
+        let c = Share {
+            x: GF256(1),
+            y: [6].iter().copied().map(GF256).collect(),
+        };
+        assert!(a.checked_add(&b).is_err());
+        assert!(a.checked_add(&c).is_err());
+    }
+
+    #[test]
+    fn mul_works() {
+        let a = Share {
+            x: GF256(1),
+            y: [2, 3].iter().copied().map(GF256).collect(),
+        };
+        assert_eq!((&a * 2).y[..], [GF256(4), GF256(6)]);
+        assert_eq!((&a * 1).y, a.y);
+    }
+
+    #[test]
+    fn affine_works() {
+        let a = Share {
+            x: GF256(1),
+            y: [2, 3].iter().copied().map(GF256).collect(),
+        };
+        let b = Share {
+            x: GF256(1),
+            y: [1, 1].iter().copied().map(GF256).collect(),
+        };
+        let share = Share::affine(vec![(2, &a), (3, &b)], &[1, 0]).unwrap();
+        assert_eq!(share.y[..], [GF256(4 ^ 3 ^ 1), GF256(6 ^ 3)]);
+        assert!(Share::affine(vec![(2, &a)], &[1]).is_err());
+        assert!(Share::affine(vec![], &[1]).is_err());
+    }
+
     #[test]
     fn vec_from_share_works() {
         let share = Share {
             x: GF256(1),
-            y: vec![GF256(2), GF256(3)],
+            y: [2, 3].iter().copied().map(GF256).collect(),
         };
         let bytes = Vec::from(&share);
         assert_eq!(bytes, vec![1, 2, 3]);
@@ -87,6 +234,6 @@ mod tests {
         let bytes = [1, 2, 3];
         let share = Share::try_from(&bytes[..]).unwrap();
         assert_eq!(share.x, GF256(1));
-        assert_eq!(share.y, vec![GF256(2), GF256(3)]);
+        assert_eq!(share.y[..], [GF256(2), GF256(3)]);
     }
 }
@@ -0,0 +1,476 @@
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+use hashbrown::HashSet;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::gf2n::{Element, Field};
+use super::secret::Secret;
+
+// The middle terms of the irreducible pentanomials `x**n + x**a + x**b + x**c + 1` used by `ssss`,
+// as `a, b, c` triples for `n = 8, 16, .., 1024`.
+const IRREDUCIBLE: [u8; 384] = [
+    4, 3, 1, 5, 3, 1, 4, 3, 1, 7, 3, 2, 5, 4, 3, 5, 3, 2, 7, 4, 2, 4, 3, 1, 10, 9, 3, 9, 4, 2, 7,
+    6, 2, 10, 9, 6, 4, 3, 1, 5, 4, 3, 4, 3, 1, 7, 2, 1, 5, 3, 2, 7, 4, 2, 6, 3, 2, 5, 3, 2, 15, 3,
+    2, 11, 3, 2, 9, 8, 7, 7, 2, 1, 5, 3, 2, 9, 3, 1, 7, 3, 1, 9, 8, 3, 9, 4, 2, 8, 5, 3, 15, 14,
+    10, 10, 5, 2, 9, 6, 2, 9, 3, 2, 9, 5, 2, 11, 10, 1, 7, 3, 2, 11, 2, 1, 9, 7, 4, 4, 3, 1, 8, 3,
+    1, 7, 4, 1, 7, 2, 1, 13, 11, 6, 5, 3, 2, 7, 3, 2, 8, 7, 5, 12, 3, 2, 13, 10, 6, 5, 3, 2, 5, 3,
+    2, 9, 5, 2, 9, 7, 2, 13, 4, 3, 4, 3, 1, 11, 6, 4, 18, 9, 6, 19, 18, 13, 11, 3, 2, 15, 9, 6, 4,
+    3, 1, 16, 5, 2, 15, 14, 6, 8, 5, 2, 15, 11, 2, 11, 6, 2, 7, 5, 3, 8, 3, 1, 19, 16, 9, 11, 9, 6,
+    15, 7, 6, 13, 4, 3, 14, 13, 3, 13, 6, 3, 9, 5, 2, 19, 13, 6, 19, 10, 3, 11, 6, 5, 9, 2, 1, 14,
+    3, 2, 13, 3, 1, 7, 5, 4, 11, 9, 8, 11, 6, 5, 23, 16, 9, 19, 14, 6, 23, 10, 2, 8, 3, 2, 5, 4, 3,
+    9, 6, 4, 4, 3, 2, 13, 8, 6, 13, 11, 1, 13, 10, 3, 11, 6, 5, 19, 17, 4, 15, 14, 7, 13, 9, 6, 9,
+    7, 3, 9, 7, 1, 14, 3, 2, 11, 8, 2, 11, 6, 4, 13, 5, 2, 11, 5, 1, 11, 4, 1, 19, 10, 3, 21, 10,
+    6, 13, 3, 1, 15, 7, 5, 19, 18, 10, 7, 5, 3, 12, 7, 2, 7, 5, 1, 14, 9, 6, 10, 3, 2, 15, 13, 12,
+    12, 11, 9, 16, 9, 7, 12, 9, 3, 9, 5, 2, 17, 10, 6, 24, 9, 3, 17, 15, 13, 5, 4, 3, 19, 17, 8,
+    15, 6, 3, 19, 6, 1,
+];
+
+fn ssss_field(degree: usize) -> Field {
+    let i = 3 * (degree / 8 - 1);
+    Field::new(
+        degree,
+        &[
+            IRREDUCIBLE[i] as usize,
+            IRREDUCIBLE[i + 1] as usize,
+            IRREDUCIBLE[i + 2] as usize,
+        ],
+    )
+}
+
+fn encipher_block(v: &mut [u32; 2]) {
+    let mut sum: u32 = 0;
+    for _ in 0..32 {
+        v[0] = v[0].wrapping_add((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+        sum = sum.wrapping_add(0x9e37_79b9);
+        v[1] = v[1].wrapping_add((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+    }
+}
+
+fn decipher_block(v: &mut [u32; 2]) {
+    let mut sum: u32 = 0xc6ef_3720;
+    for _ in 0..32 {
+        v[1] = v[1].wrapping_sub((((v[0] << 4) ^ (v[0] >> 5)).wrapping_add(v[0])) ^ sum);
+        sum = sum.wrapping_sub(0x9e37_79b9);
+        v[0] = v[0].wrapping_sub((((v[1] << 4) ^ (v[1] >> 5)).wrapping_add(v[1])) ^ sum);
+    }
+}
+
+// Runs `process` over the 8 bytes of `data` starting at `index`, wrapping around its end.
+fn process_slice(data: &mut [u8], index: usize, process: fn(&mut [u32; 2])) {
+    let len = data.len();
+    let mut v = [0u32; 2];
+    for (i, word) in v.iter_mut().enumerate() {
+        for j in 0..4 {
+            *word = (*word << 8) | data[(index + 4 * i + j) % len] as u32;
+        }
+    }
+    process(&mut v);
+    for (i, word) in v.iter().enumerate() {
+        for j in 0..4 {
+            data[(index + 4 * i + j) % len] = (word >> (24 - 8 * j)) as u8;
+        }
+    }
+}
+
+// The diffusion layer `ssss` applies to secrets of 64 bits or more: 40 rounds of XTEA with an all zero key
+// per byte, over overlapping slices of the secret. The secret is processed in the byte order of GMP's
+// `mpz_export` in 16 bits big endian words, least significant word first, which swaps each pair of bytes
+// of the big endian `bytes`.
+fn diffusion(bytes: &mut [u8], decode: bool) {
+    let len = bytes.len();
+    let position = |j: usize| {
+        let lsb = if len % 2 == 1 && j == len - 1 {
+            j
+        } else {
+            j ^ 1
+        };
+        len - 1 - lsb
+    };
+
+    let mut v: Vec<u8> = (0..len).map(|j| bytes[position(j)]).collect();
+    if decode {
+        for i in (0..40 * len).step_by(2).rev() {
+            process_slice(&mut v, i, decipher_block);
+        }
+    } else {
+        for i in (0..40 * len).step_by(2) {
+            process_slice(&mut v, i, encipher_block);
+        }
+    }
+    for (j, b) in v.iter().enumerate() {
+        bytes[position(j)] = *b;
+    }
+
+    #[cfg(feature = "zeroize_memory")]
+    v.zeroize();
+}
+
+/// Struct which implements the secret sharing scheme of B. Poettering's [`ssss`](http://point-at-infinity.org/ssss/)
+/// tool, so that shares can be exchanged with `ssss-split` and `ssss-combine`.
+///
+/// Unlike `Sharks`, `ssss` works over a binary field as large as the whole secret, from 8 to 1024 bits,
+/// and shares the secret with a monic polynomial of degree `threshold`. Unless `diffusion` is disabled,
+/// as with the `-D` flag of `ssss-split`, secrets of 64 bits or more also go through a diffusion layer.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ SsssSharks, SsssShare };
+/// # use core::str::FromStr;
+/// // Combine the shares of the example in the `ssss` manual page
+/// let sharks = SsssSharks { threshold: 3, diffusion: true };
+/// let shares: Vec<SsssShare> = [
+///     "3-fa1c3a9c6df8af0779c36de6c33f6e36e989d0e0b91309",
+///     "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+///     "2-fbc74a03a50e14ab406c225afb5f45c40ae11976d2b665",
+/// ]
+/// .iter()
+/// .map(|s| SsssShare::from_str(s).unwrap())
+/// .collect();
+/// let secret = sharks.recover(&shares).unwrap();
+/// assert_eq!(secret.expose_secret(), b"my secret root password");
+/// ```
+pub struct SsssSharks {
+    pub threshold: u8,
+    pub diffusion: bool,
+}
+
+/// A share of `SsssSharks`, which is printed and parsed in the `ssss` text format: its index, a dash and
+/// the hex encoded `y`. An optional `token-` prefix, as given with the `-w` flag of `ssss-split`, is ignored
+/// when parsing. Note that `ssss-split` pads indexes with zeros to the width of the number of shares.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct SsssShare {
+    pub x: u8,
+    pub y: Vec<u8>,
+}
+
+// `y` is not printed, only its length
+impl fmt::Debug for SsssShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SsssShare")
+            .field("x", &self.x)
+            .field("len", &self.y.len())
+            .finish()
+    }
+}
+
+impl SsssSharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares at a security level of
+    /// 8 bits per secret byte, as `ssss-split` does by default. The maximum number of shares that can be
+    /// generated is 255. If the threshold is lower than 2, or the secret is empty or longer than 128 bytes,
+    /// an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SsssSharks, SsssShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = SsssSharks { threshold: 3, diffusion: true };
+    /// // Obtain an iterator over the shares for secret "ab"
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(b"ab", &mut rng).unwrap();
+    /// // Get 3 shares, printed as `ssss-combine` expects them
+    /// let shares: Vec<String> = dealer.take(3).map(|s| s.to_string()).collect();
+    /// assert_eq!(shares[0].len(), 2 + 4);
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = SsssShare>, &'static str> {
+        if self.threshold < 2 {
+            return Err("Threshold must be at least 2");
+        }
+        if secret.is_empty() || secret.len() > 128 {
+            return Err("Secret must be between 1 and 128 bytes long");
+        }
+
+        let field = ssss_field(8 * secret.len());
+        let mut coefficients: Vec<Element> = Vec::with_capacity(self.threshold as usize);
+        let mut constant = secret.to_vec();
+        if self.diffusion && field.degree() >= 64 {
+            diffusion(&mut constant, false);
+        }
+        coefficients.push(field.read_bytes(&constant));
+        #[cfg(feature = "zeroize_memory")]
+        constant.zeroize();
+
+        let mut random = vec![0u8; secret.len()];
+        for _ in 1..self.threshold {
+            rng.fill_bytes(&mut random);
+            coefficients.push(field.read_bytes(&random));
+        }
+        #[cfg(feature = "zeroize_memory")]
+        random.zeroize();
+
+        Ok((1..=u8::MAX).map(move |x| {
+            let x_element = field.element(x as u64);
+            let mut y = x_element.clone();
+            for c in coefficients[1..].iter().rev() {
+                y = field.mul(&field.add(&y, c), &x_element);
+            }
+            y = field.add(&y, &coefficients[0]);
+
+            SsssShare {
+                x,
+                y: field.write_bytes(&y),
+            }
+        }))
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new shares at a security level of
+    /// 8 bits per secret byte, as `ssss-split` does by default. The maximum number of shares that can be
+    /// generated is 255. If the threshold is lower than 2, or the secret is empty or longer than 128 bytes,
+    /// an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SsssSharks, SsssShare };
+    /// # let sharks = SsssSharks { threshold: 3, diffusion: true };
+    /// // Obtain an iterator over the shares for secret "ab"
+    /// let dealer = sharks.dealer(b"ab").unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<SsssShare> = dealer.take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<impl Iterator<Item = SsssShare>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of shares, recovers the original secret.
+    /// As `ssss-combine` does, exactly `threshold` shares are used, the first ones with distinct `x`.
+    /// If there are not enough of them, or their lengths differ or are not between 1 and 128 bytes,
+    /// an `Err` is returned, otherwise an `Ok` containing the secret. The secret is as long as the shares,
+    /// so it is padded with leading zeros if `ssss-split` was given a security level larger than needed.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ SsssSharks, SsssShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = SsssSharks { threshold: 3, diffusion: true };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<SsssShare> = sharks.dealer_rng(&[1], &mut rng).unwrap().take(3).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove shares for demonstration purposes
+    /// shares.clear();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough shares to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a SsssShare>,
+        T::IntoIter: Iterator<Item = &'a SsssShare>,
+    {
+        let mut share_length: Option<usize> = None;
+        let mut keys: HashSet<u8> = HashSet::new();
+        let mut values: Vec<&SsssShare> = Vec::new();
+
+        for share in shares.into_iter() {
+            if share_length.is_none() {
+                share_length = Some(share.y.len());
+            }
+
+            if Some(share.y.len()) != share_length {
+                return Err("All shares must have the same length");
+            } else if keys.len() < self.threshold as usize && keys.insert(share.x) {
+                values.push(share);
+            }
+        }
+
+        if self.threshold < 2 || keys.len() < self.threshold as usize {
+            return Err("Not enough shares to recover original secret");
+        }
+        let length = values[0].y.len();
+        if length == 0 || length > 128 {
+            return Err("Shares must be between 1 and 128 bytes long");
+        }
+
+        // Remove the leading `x**threshold` term, and interpolate the rest of the polynomial at 0
+        let field = ssss_field(8 * length);
+        let xs: Vec<Element> = values.iter().map(|s| field.element(s.x as u64)).collect();
+        let mut secret = field.zero();
+
+        for (i, share) in values.iter().enumerate() {
+            let mut y = field.read_bytes(&share.y);
+            let mut power = field.element(1);
+            for _ in 0..self.threshold {
+                power = field.mul(&power, &xs[i]);
+            }
+            y = field.add(&y, &power);
+
+            let mut numerator = field.element(1);
+            let mut denominator = field.element(1);
+            for (j, x_j) in xs.iter().enumerate() {
+                if i != j {
+                    numerator = field.mul(&numerator, x_j);
+                    denominator = field.mul(&denominator, &field.add(&xs[i], x_j));
+                }
+            }
+            let coefficient = field.mul(&numerator, &field.inv(&denominator));
+            secret = field.add(&secret, &field.mul(&y, &coefficient));
+        }
+
+        let mut bytes = field.write_bytes(&secret);
+        if self.diffusion && field.degree() >= 64 {
+            diffusion(&mut bytes, true);
+        }
+
+        Ok(Secret::from(bytes))
+    }
+}
+
+/// Prints the share as `ssss-split` does, as its index, a dash and the hex encoded `y`
+impl fmt::Display for SsssShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-", self.x)?;
+        for b in &self.y {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a share in the `ssss` text format, with an optional token prefix
+impl FromStr for SsssShare {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<SsssShare, Self::Err> {
+        let mut parts = s.trim().rsplitn(3, '-');
+        let hex = parts.next().unwrap_or("");
+        let x = parts
+            .next()
+            .and_then(|x| x.parse::<u8>().ok())
+            .filter(|x| *x > 0)
+            .ok_or("Invalid share index")?;
+
+        if hex.is_empty() || hex.len() % 2 == 1 || hex.len() > 256 {
+            return Err("Share must be between 1 and 128 bytes long");
+        }
+        let y = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("-"), 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "Share is not hex encoded")?;
+
+        Ok(SsssShare { x, y })
+    }
+}
+
+/// Obtains the `ssss` text format of a share
+impl From<&SsssShare> for String {
+    fn from(s: &SsssShare) -> String {
+        alloc::format!("{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diffusion, SsssShare, SsssSharks};
+    use alloc::string::{String, ToString};
+    use alloc::{format, vec, vec::Vec};
+    use core::str::FromStr;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    // The example of the `ssss` manual page, for secret "my secret root password"
+    const SHARES: [&str; 5] = [
+        "1-1c41ef496eccfbeba439714085df8437236298da8dd824",
+        "2-fbc74a03a50e14ab406c225afb5f45c40ae11976d2b665",
+        "3-fa1c3a9c6df8af0779c36de6c33f6e36e989d0e0b91309",
+        "4-468de7d6eb36674c9cf008c8e8fc8c566537ad6301eb9e",
+        "5-4756974923c0dce0a55f4774d09ca7a4865f64f56a4ee0",
+    ];
+
+    fn sharks(threshold: u8) -> SsssSharks {
+        SsssSharks {
+            threshold,
+            diffusion: true,
+        }
+    }
+
+    #[test]
+    fn recover_ssss_shares_works() {
+        let shares: Vec<SsssShare> = SHARES
+            .iter()
+            .map(|s| SsssShare::from_str(s).unwrap())
+            .collect();
+        for window in shares.windows(3) {
+            let secret = sharks(3).recover(window).unwrap();
+            assert_eq!(secret.expose_secret(), b"my secret root password");
+        }
+        assert!(sharks(3).recover(&shares[..2]).is_err());
+        assert_ne!(
+            sharks(2).recover(&shares[..2]).unwrap().expose_secret(),
+            b"my secret root password"
+        );
+    }
+
+    #[test]
+    fn share_text_format_works() {
+        let share = SsssShare::from_str("backup-07-00ff1a").unwrap();
+        assert_eq!(share.x, 7);
+        assert_eq!(share.y, vec![0x00, 0xff, 0x1a]);
+        assert_eq!(share.to_string(), "7-00ff1a");
+        assert_eq!(String::from(&share), "7-00ff1a");
+        assert!(SsssShare::from_str("0-00ff1a").is_err());
+        assert!(SsssShare::from_str("1-00ff1").is_err());
+        assert!(SsssShare::from_str("1-00fg").is_err());
+        assert!(SsssShare::from_str("00ff").is_err());
+    }
+
+    #[test]
+    fn diffusion_works() {
+        for len in [8, 9, 23, 128].iter() {
+            let bytes: Vec<u8> = (0..*len as u8).collect();
+            let mut diffused = bytes.clone();
+            diffusion(&mut diffused, false);
+            assert_ne!(diffused, bytes);
+            diffusion(&mut diffused, true);
+            assert_eq!(diffused, bytes);
+        }
+    }
+
+    #[test]
+    fn dealer_checks_parameters() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        assert!(sharks(1).dealer_rng(&[1], &mut rng).is_err());
+        assert!(sharks(2).dealer_rng(&[], &mut rng).is_err());
+        assert!(sharks(2).dealer_rng(&[1; 129], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        for (len, diffusion) in [(1, true), (9, true), (9, false), (128, true)].iter() {
+            let sharks = SsssSharks {
+                threshold: 3,
+                diffusion: *diffusion,
+            };
+            let secret: Vec<u8> = (0..*len as u8).collect();
+            let shares: Vec<SsssShare> = sharks
+                .dealer_rng(&secret, &mut rng)
+                .unwrap()
+                .take(5)
+                .map(|s| SsssShare::from_str(&s.to_string()).unwrap())
+                .collect();
+            assert_eq!(shares[0].y.len(), *len);
+            let recovered = sharks.recover(&shares[2..]).unwrap();
+            assert_eq!(recovered.expose_secret(), secret.as_slice());
+        }
+    }
+
+    #[test]
+    fn debug_hides_y() {
+        let share = SsssShare::from_str("3-fa1c3a").unwrap();
+        assert_eq!(format!("{:?}", share), "SsssShare { x: 3, len: 3 }");
+    }
+}
@@ -0,0 +1,389 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use hashbrown::HashSet;
+use rand::seq::SliceRandom;
+
+#[cfg(feature = "zeroize_memory")]
+use zeroize::Zeroize;
+
+use super::field::{AES_POLYNOMIAL, GF256};
+use super::memory::SecretVec;
+use super::secret::Secret;
+
+// Vault works over the field defined by the AES polynomial
+fn mul(a: u8, b: u8) -> u8 {
+    (GF256::<AES_POLYNOMIAL>(a) * GF256(b)).0
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    (GF256::<AES_POLYNOMIAL>(a) / GF256(b)).0
+}
+
+// Evaluates at `x` the polynomial with the given coefficients, from higher to lower degree.
+fn evaluate(poly: &[u8], x: u8) -> u8 {
+    poly.iter().fold(0, |acc, c| mul(acc, x) ^ c)
+}
+
+/// Tuple struct which implements the Shamir's secret sharing scheme of [HashiCorp Vault](https://www.vaultproject.io/)'s
+/// `shamir` package, so that Vault unseal keys can be split and combined. Its only parameter is the minimum shares threshold.
+///
+/// Unlike `Sharks`, Vault works over the 256 bits Galois Field defined by the AES 0x11b polynomial, picks random distinct
+/// `x` coordinates and lays each share out as its `y` bytes followed by its `x` byte. Shares are thus `VaultShare` byte
+/// strings, which can be fed to `vault operator unseal` once encoded in base64.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ VaultSharks, VaultShare };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // Set a minimum threshold of 3 shares, as `vault operator init` does by default
+/// let sharks = VaultSharks(3);
+/// // Obtain an iterator over the shares for secret [1, 2, 3, 4]
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let dealer = sharks.dealer_rng(&[1, 2, 3, 4], &mut rng).unwrap();
+/// // Get 5 shares, each one a byte longer than the secret
+/// let shares: Vec<VaultShare> = dealer.take(5).collect();
+/// assert_eq!(shares[0].as_ref().len(), 5);
+/// // Recover the original secret!
+/// let secret = sharks.recover(&shares[2..]).unwrap();
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+/// ```
+pub struct VaultSharks(pub u8);
+
+/// A share of `VaultSharks`, laid out as Vault does: its `y` bytes followed by its `x` byte.
+/// With the `zeroize_memory` feature it is wiped on drop.
+///
+/// Usage example:
+/// ```
+/// use sharks::VaultShare;
+/// use core::convert::TryFrom;
+///
+/// let share = VaultShare::try_from(&[2, 3, 1][..]).unwrap();
+/// assert_eq!(share.x(), 1);
+/// assert_eq!(share.as_ref(), &[2, 3, 1]);
+/// ```
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize_memory", derive(Zeroize))]
+#[cfg_attr(feature = "zeroize_memory", zeroize(drop))]
+pub struct VaultShare(SecretVec<u8>);
+
+// The share bytes are not printed, only its `x` and length
+impl fmt::Debug for VaultShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VaultShare")
+            .field("x", &self.x())
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+impl VaultShare {
+    /// The `x` coordinate of the share, which is its last byte.
+    pub fn x(&self) -> u8 {
+        self.0[self.0.len() - 1]
+    }
+}
+
+impl AsRef<[u8]> for VaultShare {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Obtains a share from its bytes, as decoded from a Vault unseal key.
+/// If they are less than 2, an `Err` is returned.
+impl TryFrom<&[u8]> for VaultShare {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<VaultShare, Self::Error> {
+        if s.len() < 2 {
+            return Err("Shares must be at least 2 bytes long");
+        }
+        let mut bytes = SecretVec::with_capacity(s.len());
+        bytes.extend(s.iter().copied());
+        Ok(VaultShare(bytes))
+    }
+}
+
+impl VaultSharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns an `Iterator` along new Vault shares.
+    /// The maximum number of shares that can be generated is 255.
+    /// As Vault does, if the threshold is lower than 2 or the secret is empty, an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ VaultSharks, VaultShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = VaultSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let dealer = sharks.dealer_rng(&[1, 2], &mut rng).unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<VaultShare> = dealer.take(3).collect();
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<impl Iterator<Item = VaultShare>, &'static str> {
+        if self.0 < 2 {
+            return Err("Threshold must be at least 2");
+        }
+        if secret.is_empty() {
+            return Err("Secret can not be empty");
+        }
+
+        // The polynomials of every secret byte are stored one after another, in a single allocation
+        let k = self.0 as usize;
+        let mut polys: SecretVec<u8> = SecretVec::with_capacity(secret.len() * k);
+        for chunk in secret {
+            polys.extend((1..self.0).map(|_| rng.gen_range(1..=255)));
+            polys.push(*chunk);
+        }
+
+        let mut xs: Vec<u8> = (1..=255).collect();
+        xs.shuffle(rng);
+
+        Ok(xs.into_iter().map(move |x| {
+            let mut share: SecretVec<u8> = SecretVec::with_capacity(polys.len() / k + 1);
+            share.extend(polys.chunks(k).map(|p| evaluate(p, x)));
+            share.push(x);
+            VaultShare(share)
+        }))
+    }
+
+    /// Given a `secret` byte slice, returns an `Iterator` along new Vault shares.
+    /// The maximum number of shares that can be generated is 255.
+    /// As Vault does, if the threshold is lower than 2 or the secret is empty, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ VaultSharks, VaultShare };
+    /// # let sharks = VaultSharks(3);
+    /// // Obtain an iterator over the shares for secret [1, 2]
+    /// let dealer = sharks.dealer(&[1, 2]).unwrap();
+    /// // Get 3 shares
+    /// let shares: Vec<VaultShare> = dealer.take(3).collect();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<impl Iterator<Item = VaultShare>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of Vault shares, recovers the original secret.
+    /// As Vault does, all the shares are used, so an `Err` is returned if any of them is shorter than 2 bytes,
+    /// their lengths differ or two of them have the same `x`. An `Err` is also returned if the number of shares
+    /// is less than the minimum threshold, otherwise an `Ok` containing the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ VaultSharks, VaultShare };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = VaultSharks(3);
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut shares: Vec<VaultShare> = sharks.dealer_rng(&[1], &mut rng).unwrap().take(3).collect();
+    /// // Recover original secret from shares
+    /// let mut secret = sharks.recover(&shares);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove shares for demonstration purposes
+    /// shares.clear();
+    /// secret = sharks.recover(&shares);
+    /// // Not enough shares to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T, S>(&self, shares: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a S>,
+        T::IntoIter: Iterator<Item = &'a S>,
+        S: AsRef<[u8]> + ?Sized + 'a,
+    {
+        let mut share_length: Option<usize> = None;
+        let mut keys: HashSet<u8> = HashSet::new();
+        let mut values: Vec<&[u8]> = Vec::new();
+
+        for share in shares.into_iter() {
+            let share = share.as_ref();
+            if share_length.is_none() {
+                share_length = Some(share.len());
+            }
+
+            if share.len() < 2 {
+                return Err("Shares must be at least 2 bytes long");
+            } else if Some(share.len()) != share_length {
+                return Err("All shares must have the same length");
+            } else if !keys.insert(share[share.len() - 1]) {
+                return Err("Duplicate share detected");
+            } else {
+                values.push(share);
+            }
+        }
+
+        if keys.len() < 2 || keys.len() < self.0 as usize {
+            return Err("Not enough shares to recover original secret");
+        }
+
+        // Lagrange basis polynomials evaluated at 0
+        let xs: Vec<u8> = values.iter().map(|s| s[s.len() - 1]).collect();
+        let coefficients: Vec<u8> = xs
+            .iter()
+            .map(|x_i| {
+                xs.iter()
+                    .filter(|x_j| *x_j != x_i)
+                    .fold(1, |acc, x_j| mul(acc, div(*x_j, x_j ^ x_i)))
+            })
+            .collect();
+
+        let length = values[0].len() - 1;
+        let mut secret = SecretVec::with_capacity(length);
+        for b in 0..length {
+            secret.push(
+                values
+                    .iter()
+                    .zip(coefficients.iter())
+                    .fold(0, |acc, (s, l)| acc ^ mul(s[b], *l)),
+            );
+        }
+
+        Ok(Secret(secret))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{div, mul, VaultShare, VaultSharks};
+    use alloc::{format, vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    // Unseal keys printed by `vault operator init` in Vault's documentation, decoded from base64,
+    // for a threshold of 3 at x = 0xa6, 0x92, 0x40, 0xfe and 0xa4
+    const SHARES: [[u8; 33]; 5] = [
+        [
+            0xe2, 0x36, 0x1b, 0x97, 0x60, 0x81, 0x22, 0xfe, 0x92, 0xa6, 0x42, 0xa3, 0xe8, 0x7a,
+            0x2c, 0xf6, 0x20, 0xf7, 0xda, 0x4e, 0x51, 0x7c, 0x69, 0x0b, 0xce, 0x5a, 0x2c, 0xae,
+            0xba, 0xbf, 0x26, 0x03, 0xa6,
+        ],
+        [
+            0x07, 0x4e, 0x46, 0xd4, 0x34, 0x6d, 0x7d, 0x87, 0x24, 0x15, 0x5e, 0x41, 0x6d, 0xd0,
+            0x6f, 0x5e, 0xad, 0x30, 0x90, 0xae, 0x47, 0x16, 0xa0, 0x7d, 0x83, 0x68, 0xdc, 0x0e,
+            0x63, 0x5f, 0x4d, 0x08, 0x92,
+        ],
+        [
+            0x02, 0xb8, 0xa0, 0xd0, 0xdf, 0x6b, 0x37, 0xd7, 0xb3, 0x91, 0x34, 0x68, 0xee, 0xa4,
+            0xc1, 0xee, 0x0b, 0x08, 0x64, 0x36, 0xa8, 0x9c, 0xe7, 0x1c, 0xe7, 0x71, 0x07, 0xa3,
+            0xcd, 0xc5, 0xe5, 0xc8, 0x40,
+        ],
+        [
+            0xd1, 0xc6, 0x44, 0xd0, 0x2f, 0xe0, 0x12, 0x4d, 0xd8, 0x1d, 0xa2, 0xa3, 0x21, 0x6c,
+            0x61, 0xcb, 0x27, 0xec, 0xf1, 0x11, 0x21, 0xaa, 0x44, 0x56, 0xfc, 0x24, 0x97, 0x4e,
+            0x79, 0x93, 0x8a, 0x5b, 0xfe,
+        ],
+        [
+            0x7d, 0x88, 0x59, 0x3a, 0xc7, 0x91, 0x83, 0x3c, 0x66, 0x24, 0x29, 0x88, 0xa9, 0x47,
+            0x71, 0x12, 0x6f, 0x42, 0xde, 0x30, 0x79, 0x43, 0x6e, 0xc0, 0xa3, 0x01, 0x11, 0xf7,
+            0x0e, 0x05, 0x0b, 0x60, 0xa4,
+        ],
+    ];
+
+    // The root key those unseal keys recover
+    const ROOT_KEY: [u8; 32] = [
+        0xd2, 0x4e, 0x15, 0xf5, 0xd9, 0x92, 0x3e, 0xf2, 0x40, 0xb2, 0x69, 0xf0, 0x5a, 0xec, 0x3e,
+        0x3f, 0x74, 0x73, 0x1e, 0x9d, 0x43, 0xa8, 0x86, 0xe4, 0x0c, 0x9f, 0xf6, 0xe7, 0x2c, 0x35,
+        0x85, 0x35,
+    ];
+
+    #[test]
+    fn field_matches_vault() {
+        // From Vault's `shamir_test.go`
+        assert_eq!(mul(3, 7), 9);
+        assert_eq!(mul(3, 0), 0);
+        assert_eq!(mul(0, 3), 0);
+        assert_eq!(div(0, 7), 0);
+        assert_eq!(div(3, 3), 1);
+        assert_eq!(div(6, 3), 2);
+        // Products which need a reduction by the AES polynomial, from FIPS 197
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x53, 0xca), 0x01);
+        assert_eq!(div(0x01, 0xca), 0x53);
+    }
+
+    #[test]
+    fn recover_vault_shares_works() {
+        let sharks = VaultSharks(3);
+        // Every subset of 3 unseal keys
+        for skipped in 0..5 * 5 {
+            let (a, b) = (skipped / 5, skipped % 5);
+            if a < b {
+                let shares = SHARES
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != a && *i != b)
+                    .map(|(_, s)| s);
+                assert_eq!(sharks.recover(shares).unwrap().expose_secret(), &ROOT_KEY);
+            }
+        }
+        assert_eq!(sharks.recover(&SHARES).unwrap().expose_secret(), &ROOT_KEY);
+        assert!(sharks.recover(&SHARES[..2]).is_err());
+        assert_ne!(
+            VaultSharks(2)
+                .recover(&SHARES[..2])
+                .unwrap()
+                .expose_secret(),
+            &ROOT_KEY
+        );
+    }
+
+    #[test]
+    fn test_invalid_shares_err() {
+        let sharks = VaultSharks(2);
+        assert!(sharks.recover(vec![&SHARES[0], &SHARES[0]]).is_err());
+        assert!(sharks
+            .recover(vec![&SHARES[0][..6], &SHARES[1][..]])
+            .is_err());
+        assert!(sharks
+            .recover(vec![&SHARES[0][..1], &SHARES[1][..1]])
+            .is_err());
+    }
+
+    #[test]
+    fn share_conversion_works() {
+        let share = VaultShare::try_from(&SHARES[0][..]).unwrap();
+        assert_eq!(share.x(), 0xa6);
+        assert_eq!(share.as_ref(), &SHARES[0][..]);
+        assert!(VaultShare::try_from(&SHARES[0][..1]).is_err());
+    }
+
+    #[test]
+    fn dealer_checks_parameters() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        assert!(VaultSharks(1).dealer_rng(&[1], &mut rng).is_err());
+        assert!(VaultSharks(2).dealer_rng(&[], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let sharks = VaultSharks(5);
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let shares: Vec<VaultShare> = sharks
+            .dealer_rng(&[1, 2, 3, 4], &mut rng)
+            .unwrap()
+            .collect();
+        assert_eq!(shares.len(), 255);
+        let mut xs: Vec<u8> = shares.iter().map(|s| s.x()).collect();
+        xs.sort_unstable();
+        assert_eq!(xs, (1..=255).collect::<Vec<u8>>());
+        assert_eq!(
+            sharks.recover(&shares[250..]).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn debug_hides_share_bytes() {
+        let share = VaultShare::try_from(&[2, 3, 1][..]).unwrap();
+        assert_eq!(format!("{:?}", share), "VaultShare { x: 1, len: 3 }");
+    }
+}
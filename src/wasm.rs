@@ -0,0 +1,133 @@
+//! WebAssembly bindings, enabled with the `wasm` feature, for splitting and recovering secrets in the browser.
+//!
+//! Shares are generated with the operating system RNG, which is `crypto.getRandomValues` in browsers
+//! and Node. They cross the boundary as `Uint8Array`s, laid out as the `x` byte followed by the `y` bytes,
+//! or as the hex encoding of those bytes. Errors are thrown as JavaScript `Error`s.
+//!
+//! ```js
+//! import { split, splitHex, combine } from "sharks";
+//!
+//! const shares = splitHex(new TextEncoder().encode("recovery key"), 3, 5);
+//! const secret = combine([shares[0], shares[2], shares[4]], 3);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::Write;
+
+use js_sys::{Array, Uint8Array};
+use rand::rngs::OsRng;
+use wasm_bindgen::prelude::*;
+
+use super::share::Share;
+use super::Sharks;
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{:02x}", b);
+        hex
+    })
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, &'static str> {
+    if hex.len() % 2 == 1 {
+        return Err("Hex share must have an even length");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or("Share is not hex encoded")
+        })
+        .collect()
+}
+
+// Generates `count` shares of `secret` with the operating system RNG
+fn deal(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<Share>, &'static str> {
+    if threshold == 0 || count < threshold {
+        return Err("The number of shares must be at least the threshold, which can not be zero");
+    }
+    if secret.is_empty() {
+        return Err("Secret can not be empty");
+    }
+
+    Ok(Sharks(threshold)
+        .dealer_rng(secret, &mut OsRng)
+        .take(count as usize)
+        .collect())
+}
+
+// Reads a share given as a `Uint8Array` or a hex string
+fn read_share(value: JsValue) -> Result<Share, &'static str> {
+    let bytes = match value.as_string() {
+        Some(hex) => decode_hex(hex.trim())?,
+        None if value.is_instance_of::<Uint8Array>() => Uint8Array::from(value).to_vec(),
+        None => return Err("Shares must be Uint8Arrays or hex strings"),
+    };
+    Share::try_from(bytes.as_slice())
+}
+
+/// Splits `secret` into `count` shares, any `threshold` of which recover it, returned as an array of `Uint8Array`s.
+#[wasm_bindgen]
+pub fn split(secret: &[u8], threshold: u8, count: u8) -> Result<Array, JsError> {
+    Ok(deal(secret, threshold, count)
+        .map_err(JsError::new)?
+        .iter()
+        .map(|share| JsValue::from(Uint8Array::from(Vec::from(share).as_slice())))
+        .collect())
+}
+
+/// Splits `secret` into `count` shares, any `threshold` of which recover it, returned as an array of hex strings.
+#[wasm_bindgen(js_name = splitHex)]
+pub fn split_hex(secret: &[u8], threshold: u8, count: u8) -> Result<Array, JsError> {
+    Ok(deal(secret, threshold, count)
+        .map_err(JsError::new)?
+        .iter()
+        .map(|share| JsValue::from(encode_hex(&Vec::from(share))))
+        .collect())
+}
+
+/// Recovers the secret from an array of shares, given as `Uint8Array`s or hex strings.
+/// Throws if `threshold` is zero, there are less distinct shares than it, or their lengths differ.
+#[wasm_bindgen]
+pub fn combine(shares: Array, threshold: u8) -> Result<Uint8Array, JsError> {
+    // Any share would be taken for the secret
+    if threshold == 0 {
+        return Err(JsError::new("Threshold can not be zero"));
+    }
+    let shares = shares
+        .iter()
+        .map(read_share)
+        .collect::<Result<Vec<Share>, _>>()
+        .map_err(JsError::new)?;
+    let secret = Sharks(threshold).recover(&shares)?;
+    Ok(Uint8Array::from(secret.expose_secret()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deal, decode_hex, encode_hex};
+    use crate::Sharks;
+
+    #[test]
+    fn hex_works() {
+        assert_eq!(encode_hex(&[0x00, 0xab, 0x1f]), "00ab1f");
+        assert_eq!(decode_hex("00AB1f").unwrap(), [0x00, 0xab, 0x1f]);
+        assert!(decode_hex("0ab").is_err());
+        assert!(decode_hex("0g").is_err());
+    }
+
+    #[test]
+    fn deal_works() {
+        let shares = deal(&[1, 2, 3], 2, 3).unwrap();
+        assert_eq!(shares.len(), 3);
+        let secret = Sharks(2).recover(&shares[1..]).unwrap();
+        assert_eq!(secret.expose_secret(), &[1, 2, 3]);
+
+        assert!(deal(&[1, 2, 3], 3, 2).is_err());
+        assert!(deal(&[1, 2, 3], 0, 2).is_err());
+        assert!(deal(&[], 2, 2).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use hashbrown::HashSet;
+
+use super::secret::Secret;
+use super::share::Share;
+use super::Sharks;
+
+/// Struct which implements weighted threshold secret sharing.
+/// Each holder ID in `weights` is handed a bundle with as many ordinary shares as its weight,
+/// and the secret is recovered once the weights of the gathered bundles add up to the `threshold`.
+///
+/// Usage example:
+/// ```
+/// # use sharks::{ WeightedSharks, ShareBundle };
+/// # use rand_chacha::rand_core::SeedableRng;
+/// // The CISO (holder 0) counts as three board members (holders 1 to 5)
+/// let sharks = WeightedSharks {
+///     threshold: 4,
+///     weights: [(0, 3), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1)].iter().cloned().collect(),
+/// };
+/// // Obtain the bundles for secret [1, 2, 3, 4]
+/// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+/// let bundles: Vec<ShareBundle> = sharks.dealer_rng(&[1, 2, 3, 4], &mut rng).unwrap();
+/// // The CISO and a board member can recover the secret!
+/// let secret = sharks.recover(&bundles[..2]).unwrap();
+/// assert_eq!(secret.expose_secret(), &[1, 2, 3, 4]);
+/// // Three board members can not
+/// assert!(sharks.recover(&bundles[1..4]).is_err());
+/// ```
+pub struct WeightedSharks {
+    pub threshold: u8,
+    pub weights: BTreeMap<u8, u8>,
+}
+
+/// The bundle of shares handed to a holder of `WeightedSharks`. Can be serialized to and from a byte array
+/// as a unit.
+#[derive(Clone)]
+pub struct ShareBundle {
+    pub holder: u8,
+    pub shares: Vec<Share>,
+}
+
+impl WeightedSharks {
+    /// This method is useful when `std` is not available. For typical usage
+    /// see the `dealer` method.
+    ///
+    /// Given a `secret` byte slice, returns one bundle of shares for each holder, in holder ID order.
+    /// If the threshold or any weight is zero, or the weights add up to more than 255 or to less than the
+    /// threshold, an `Err` is returned.
+    /// A random number generator has to be provided.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ WeightedSharks, ShareBundle };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = WeightedSharks { threshold: 2, weights: [(7, 2), (9, 1)].iter().cloned().collect() };
+    /// // Obtain the bundles for secret [1, 2]
+    /// let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// let bundles: Vec<ShareBundle> = sharks.dealer_rng(&[1, 2], &mut rng).unwrap();
+    /// assert_eq!(bundles[0].holder, 7);
+    /// assert_eq!(bundles[0].shares.len(), 2);
+    pub fn dealer_rng<R: rand::Rng>(
+        &self,
+        secret: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<ShareBundle>, &'static str> {
+        if self.threshold == 0 {
+            return Err("Threshold can not be zero");
+        }
+        if self.weights.values().any(|w| *w == 0) {
+            return Err("Weights must be positive");
+        }
+        let total = self.weights.values().map(|w| *w as usize).sum::<usize>();
+        if total > 255 {
+            return Err("Weights can not add up to more than 255");
+        }
+        if total < self.threshold as usize {
+            return Err("Weights can not add up to less than the threshold");
+        }
+
+        let mut dealer = Sharks(self.threshold).dealer_rng(secret, rng);
+        Ok(self
+            .weights
+            .iter()
+            .map(|(holder, weight)| ShareBundle {
+                holder: *holder,
+                shares: dealer.by_ref().take(*weight as usize).collect(),
+            })
+            .collect())
+    }
+
+    /// Given a `secret` byte slice, returns one bundle of shares for each holder, in holder ID order.
+    /// If the threshold or any weight is zero, or the weights add up to more than 255 or to less than the
+    /// threshold, an `Err` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ WeightedSharks, ShareBundle };
+    /// # let sharks = WeightedSharks { threshold: 2, weights: [(7, 2), (9, 1)].iter().cloned().collect() };
+    /// // Obtain the bundles for secret [1, 2]
+    /// let bundles: Vec<ShareBundle> = sharks.dealer(&[1, 2]).unwrap();
+    #[cfg(feature = "std")]
+    pub fn dealer(&self, secret: &[u8]) -> Result<Vec<ShareBundle>, &'static str> {
+        let mut rng = rand::thread_rng();
+        self.dealer_rng(secret, &mut rng)
+    }
+
+    /// Given an iterable collection of bundles, recovers the original secret.
+    /// If a bundle belongs to an unknown holder or doesn't match its weight, or the weights of the distinct
+    /// holders add up to less than the minimum threshold, an `Err` is returned, otherwise an `Ok` containing
+    /// the secret.
+    ///
+    /// Example:
+    /// ```
+    /// # use sharks::{ WeightedSharks, ShareBundle };
+    /// # use rand_chacha::rand_core::SeedableRng;
+    /// # let sharks = WeightedSharks { threshold: 3, weights: [(7, 2), (9, 1)].iter().cloned().collect() };
+    /// # let mut rng = rand_chacha::ChaCha8Rng::from_seed([0x90; 32]);
+    /// # let mut bundles: Vec<ShareBundle> = sharks.dealer_rng(&[1], &mut rng).unwrap();
+    /// // Recover original secret from bundles
+    /// let mut secret = sharks.recover(&bundles);
+    /// // Secret correctly recovered
+    /// assert!(secret.is_ok());
+    /// // Remove a bundle for demonstration purposes
+    /// bundles.pop();
+    /// secret = sharks.recover(&bundles);
+    /// // Not enough weight to recover secret
+    /// assert!(secret.is_err());
+    pub fn recover<'a, T>(&self, bundles: T) -> Result<Secret, &'static str>
+    where
+        T: IntoIterator<Item = &'a ShareBundle>,
+        T::IntoIter: Iterator<Item = &'a ShareBundle>,
+    {
+        let mut holders: HashSet<u8> = HashSet::new();
+        let mut weight = 0;
+        let mut values: Vec<&Share> = Vec::new();
+
+        for bundle in bundles.into_iter() {
+            match self.weights.get(&bundle.holder) {
+                None => return Err("Bundle belongs to an unknown holder"),
+                Some(w) if *w as usize != bundle.shares.len() => {
+                    return Err("Bundle size doesn't match the holder weight")
+                }
+                Some(w) => {
+                    if holders.insert(bundle.holder) {
+                        weight += *w as usize;
+                        values.extend(bundle.shares.iter());
+                    }
+                }
+            }
+        }
+
+        if weight < self.threshold as usize {
+            Err("Not enough weight to recover original secret")
+        } else {
+            Sharks(self.threshold).recover(values).map_err(Into::into)
+        }
+    }
+}
+
+/// Obtains a byte vector from a `ShareBundle` instance.
+/// The layout is the holder ID, the number of shares, and then each share's bytes.
+impl From<&ShareBundle> for Vec<u8> {
+    fn from(b: &ShareBundle) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(b.holder);
+        bytes.push(b.shares.len() as u8);
+        for share in &b.shares {
+            bytes.extend(Vec::from(share));
+        }
+        bytes
+    }
+}
+
+/// Obtains a `ShareBundle` instance from a byte slice
+impl TryFrom<&[u8]> for ShareBundle {
+    type Error = &'static str;
+
+    fn try_from(s: &[u8]) -> Result<ShareBundle, Self::Error> {
+        if s.len() < 4 || s[1] == 0 {
+            Err("A ShareBundle must hold at least one share")
+        } else if !(s.len() - 2).is_multiple_of(s[1] as usize) {
+            Err("All shares in a ShareBundle must have the same length")
+        } else {
+            let shares = s[2..]
+                .chunks((s.len() - 2) / s[1] as usize)
+                .map(Share::try_from)
+                .collect::<Result<Vec<Share>, _>>()?;
+            Ok(ShareBundle {
+                holder: s[0],
+                shares,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShareBundle, WeightedSharks};
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn make_sharks(threshold: u8, weights: &[(u8, u8)]) -> WeightedSharks {
+        WeightedSharks {
+            threshold,
+            weights: weights.iter().cloned().collect(),
+        }
+    }
+
+    fn make_bundles(sharks: &WeightedSharks, secret: &[u8]) -> Vec<ShareBundle> {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        sharks.dealer_rng(secret, &mut rng).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_weights_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let sharks = make_sharks(2, &[(0, 200), (1, 56)]);
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_err());
+        let sharks = make_sharks(2, &[(0, 2), (1, 0)]);
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_invalid_threshold_err() {
+        let mut rng = ChaCha8Rng::from_seed([0x90; 32]);
+        let sharks = make_sharks(0, &[(0, 2), (1, 1)]);
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_err());
+        let sharks = make_sharks(4, &[(0, 2), (1, 1)]);
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_err());
+        let sharks = make_sharks(3, &[(0, 2), (1, 1)]);
+        assert!(sharks.dealer_rng(&[1], &mut rng).is_ok());
+    }
+
+    #[test]
+    fn test_insufficient_weight_err() {
+        let sharks = make_sharks(4, &[(0, 3), (1, 1), (2, 1)]);
+        let bundles = make_bundles(&sharks, &[1, 2]);
+        assert!(sharks.recover(&bundles[1..]).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_bundles_err() {
+        let sharks = make_sharks(4, &[(0, 3), (1, 1)]);
+        let bundles = make_bundles(&sharks, &[1, 2]);
+        assert!(sharks.recover(vec![&bundles[0], &bundles[0]]).is_err());
+    }
+
+    #[test]
+    fn test_tampered_bundle_err() {
+        let sharks = make_sharks(2, &[(0, 2), (1, 1)]);
+        let mut bundles = make_bundles(&sharks, &[1, 2]);
+        bundles[1].holder = 2;
+        assert!(sharks.recover(&bundles).is_err());
+        bundles[1].holder = 0;
+        assert!(sharks.recover(&bundles[1..]).is_err());
+    }
+
+    #[test]
+    fn test_integration_works() {
+        let sharks = make_sharks(10, &[(3, 5), (10, 1), (20, 4), (30, 245)]);
+        let bundles = make_bundles(&sharks, &[1, 2, 3, 4]);
+        assert_eq!(
+            sharks.recover(&bundles[..3]).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+        assert_eq!(
+            sharks.recover(&bundles[3..]).unwrap().expose_secret(),
+            &[1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn bundle_serialization_works() {
+        let sharks = make_sharks(3, &[(5, 2), (6, 1)]);
+        let bundles: Vec<ShareBundle> = make_bundles(&sharks, &[1, 2])
+            .iter()
+            .map(|b| ShareBundle::try_from(Vec::from(b).as_slice()).unwrap())
+            .collect();
+        assert_eq!(Vec::from(&bundles[0]).len(), 2 + 2 * 3);
+        assert_eq!(bundles[0].holder, 5);
+        assert_eq!(sharks.recover(&bundles).unwrap().expose_secret(), &[1, 2]);
+        assert!(ShareBundle::try_from(&[5, 2, 1, 2, 3][..]).is_err());
+        assert!(ShareBundle::try_from(&[5, 0, 1, 2][..]).is_err());
+    }
+}
@@ -0,0 +1,423 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{self, Command, Output, Stdio};
+
+fn sharks(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sharks"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The process may exit before reading its input, for instance on invalid arguments
+    let _ = child.stdin.take().unwrap().write_all(stdin);
+    child.wait_with_output().unwrap()
+}
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sharks-cli-{}-{}", test, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn split_and_combine_files_works() {
+    let dir = temp_dir("files");
+    let secret = dir.join("secret.txt");
+    fs::write(&secret, b"correct horse battery staple").unwrap();
+    let shares = dir.join("shares");
+
+    for encoding in ["raw", "hex", "base64"].iter() {
+        let _ = fs::remove_dir_all(&shares);
+        let output = sharks(
+            &[
+                "split",
+                "-k",
+                "3",
+                "-n",
+                "5",
+                secret.to_str().unwrap(),
+                "-o",
+                shares.to_str().unwrap(),
+                "-e",
+                encoding,
+            ],
+            b"",
+        );
+        assert!(output.status.success());
+
+        let mut paths: Vec<String> = fs::read_dir(&shares)
+            .unwrap()
+            .map(|e| e.unwrap().path().to_str().unwrap().to_owned())
+            .collect();
+        assert_eq!(paths.len(), 5);
+        paths.sort();
+
+        let mut args = vec!["combine", "-k", "3", "-e", encoding];
+        args.extend(paths[2..].iter().map(|p| p.as_str()));
+        let output = sharks(&args, b"");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"correct horse battery staple");
+    }
+
+    // The threshold defaults to the one the shares were dealt with, as in `sharks combine shares/*`
+    let _ = fs::remove_dir_all(&shares);
+    let output = sharks(
+        &[
+            "split",
+            "-k",
+            "3",
+            "-n",
+            "5",
+            secret.to_str().unwrap(),
+            "-o",
+            shares.to_str().unwrap(),
+        ],
+        b"",
+    );
+    assert!(output.status.success());
+    let paths: Vec<String> = fs::read_dir(&shares)
+        .unwrap()
+        .map(|e| e.unwrap().path().to_str().unwrap().to_owned())
+        .collect();
+    let mut args = vec!["combine"];
+    args.extend(paths.iter().map(|p| p.as_str()));
+    let output = sharks(&args, b"");
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"correct horse battery staple");
+
+    // Shares are never overwritten
+    let args = ["split", "-k", "2", "-n", "2", secret.to_str().unwrap()];
+    let output = sharks(
+        &[&args[..], &["-o", shares.to_str().unwrap()]].concat(),
+        b"",
+    );
+    assert!(!output.status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn split_and_combine_stdio_works() {
+    let output = sharks(
+        &["split", "-k", "2", "-n", "3", "-e", "base64"],
+        b"\x00\x01\x02",
+    );
+    assert!(output.status.success());
+    let shares = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(shares.lines().count(), 3);
+
+    let output = sharks(&["combine", "-e", "base64", "-k", "2"], shares.as_bytes());
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"\x00\x01\x02");
+
+    let first = shares.lines().next().unwrap();
+    let output = sharks(&["combine", "-e", "base64", "-k", "2"], first.as_bytes());
+    assert!(!output.status.success());
+}
+
+#[test]
+fn invalid_arguments_fail() {
+    let output = sharks(&["split", "-k", "3", "-n", "2"], b"secret");
+    assert!(!output.status.success());
+    let output = sharks(&["split", "-k", "2", "-n", "3", "-e", "raw"], b"secret");
+    assert!(!output.status.success());
+    let output = sharks(&["split", "-k", "2", "-n", "3"], b"");
+    assert!(!output.status.success());
+    let output = sharks(&["combine"], b"");
+    assert!(!output.status.success());
+    let output = sharks(&["combine", "-k", "0"], b"");
+    assert!(!output.status.success());
+    let output = sharks(&["combine", "-k", "1"], b"not hex\n");
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("sharks: "));
+}
+
+#[test]
+fn inspect_and_verify_work() {
+    let output = sharks(&["split", "-k", "2", "-n", "3"], b"secret");
+    let shares = String::from_utf8(output.stdout).unwrap();
+
+    let output = sharks(&["inspect"], shares.as_bytes());
+    assert!(output.status.success());
+    let lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| l.to_owned())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    let set = &lines[0][lines[0].find("set = ").unwrap() + 6..][..32];
+    for (i, line) in lines.iter().enumerate() {
+        assert_eq!(
+            line,
+            &format!(
+                "-:{}: version = 1, set = {}, threshold = 2 of 3, x = {}, length = 6 bytes",
+                i + 1,
+                set,
+                i + 1
+            )
+        );
+    }
+
+    let output = sharks(&["verify"], shares.as_bytes());
+    assert!(output.status.success());
+    assert!(output
+        .stdout
+        .starts_with(format!("OK: 3 distinct shares of 6 bytes from set {}", set).as_bytes()));
+    assert!(output.stderr.is_empty());
+    // The consistency of exactly the threshold of shares can not be checked
+    let two: String = shares.lines().take(2).map(|l| format!("{}\n", l)).collect();
+    let output = sharks(&["verify", "-k", "2"], two.as_bytes());
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("whether they lie on the same polynomial can not be checked"));
+    let output = sharks(&["verify", "-k", "4"], shares.as_bytes());
+    assert!(!output.status.success());
+
+    // Corrupt the checksum of the last share
+    let mut corrupted = shares.clone().into_bytes();
+    let last = corrupted.len() - 2;
+    corrupted[last] = if corrupted[last] == b'0' { b'1' } else { b'0' };
+    let output = sharks(&["verify", "-k", "2"], &corrupted);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("-:3: Share envelope checksum does not match"));
+
+    // Mix in a share of another set
+    let output = sharks(&["split", "-k", "2", "-n", "3"], b"secret");
+    let other = String::from_utf8(output.stdout).unwrap();
+    let mixed = format!(
+        "{}\n{}",
+        shares.lines().next().unwrap(),
+        other.lines().nth(1).unwrap()
+    );
+    let output = sharks(&["verify", "-k", "2"], mixed.as_bytes());
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("different sets"));
+}
+
+// A `sharks` process attached to a pseudo-terminal, driven as a participant would
+#[cfg(unix)]
+struct Terminal {
+    child: std::process::Child,
+    master: fs::File,
+    // Kept open to read the terminal settings once the process exits
+    slave: fs::File,
+    output: std::sync::mpsc::Receiver<u8>,
+    screen: Vec<u8>,
+}
+
+#[cfg(unix)]
+impl Terminal {
+    fn spawn(args: &[&str]) -> Terminal {
+        use std::os::unix::io::FromRawFd;
+
+        let (mut master, mut slave) = (0, 0);
+        let opened = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        assert_eq!(opened, 0);
+        let master = unsafe { fs::File::from_raw_fd(master) };
+        let slave = unsafe { fs::File::from_raw_fd(slave) };
+
+        // The terminal is made the controlling one of the process, so that Ctrl-C interrupts it
+        let mut command = Command::new(env!("CARGO_BIN_EXE_sharks"));
+        command
+            .args(args)
+            .stdin(slave.try_clone().unwrap())
+            .stdout(slave.try_clone().unwrap())
+            .stderr(slave.try_clone().unwrap());
+        unsafe {
+            std::os::unix::process::CommandExt::pre_exec(&mut command, || {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = command.spawn().unwrap();
+
+        // Reads until the process exits and the terminal is closed
+        let (sender, output) = std::sync::mpsc::channel();
+        let mut reader = master.try_clone().unwrap();
+        std::thread::spawn(move || {
+            let mut buffer = [0; 256];
+            while let Ok(n) = std::io::Read::read(&mut reader, &mut buffer) {
+                if n == 0 || buffer[..n].iter().any(|b| sender.send(*b).is_err()) {
+                    break;
+                }
+            }
+        });
+
+        Terminal {
+            child,
+            master,
+            slave,
+            output,
+            screen: Vec::new(),
+        }
+    }
+
+    fn expect(&mut self, text: &str) {
+        while !String::from_utf8_lossy(&self.screen).contains(text) {
+            let byte = self
+                .output
+                .recv_timeout(std::time::Duration::from_secs(10))
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "{:?} not shown in {:?}",
+                        text,
+                        String::from_utf8_lossy(&self.screen)
+                    )
+                });
+            self.screen.push(byte);
+        }
+    }
+
+    fn echoes(&self) -> bool {
+        use std::os::unix::io::AsRawFd;
+
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        assert_eq!(
+            unsafe { libc::tcgetattr(self.slave.as_raw_fd(), &mut termios) },
+            0
+        );
+        termios.c_lflag & libc::ECHO != 0
+    }
+
+    fn type_line(&mut self, line: &str) {
+        self.master.write_all(line.as_bytes()).unwrap();
+        self.master.write_all(b"\n").unwrap();
+    }
+
+    fn finish(mut self) -> (bool, String) {
+        let status = self.child.wait().unwrap();
+        while let Ok(byte) = self
+            .output
+            .recv_timeout(std::time::Duration::from_millis(200))
+        {
+            self.screen.push(byte);
+        }
+        (
+            status.success(),
+            String::from_utf8_lossy(&self.screen).into_owned(),
+        )
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn ceremony_works() {
+    let output = sharks(&["split", "-k", "3", "-n", "5"], b"launch codes");
+    let shares: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| l.to_owned())
+        .collect();
+
+    let mut terminal = Terminal::spawn(&["ceremony", "-k", "3"]);
+    terminal.expect("Share 1 of 3: ");
+    terminal.type_line(&shares[0]);
+    terminal.expect("Accepted share x = 1 (1/3)");
+
+    // Typos and conflicting shares are rejected right away
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(&shares[3][1..]);
+    terminal.expect("Rejected: Hex share must have an even length");
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(&shares[3][..shares[3].len() - 2]);
+    terminal.expect("Rejected: Share envelope checksum does not match, it may have a typo");
+    terminal.expect("Share 2 of 3: ");
+    let mut mistyped = shares[3].clone().into_bytes();
+    let middle = mistyped.len() / 2;
+    mistyped[middle] = if mistyped[middle] == b'7' { b'8' } else { b'7' };
+    terminal.type_line(std::str::from_utf8(&mistyped).unwrap());
+    terminal.expect("Rejected: Share envelope checksum does not match, it may have a typo");
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(&shares[0]);
+    terminal.expect("Rejected: Share x = 1 was already entered");
+    let output = sharks(&["split", "-k", "3", "-n", "5"], b"launch codes");
+    let other = String::from_utf8(output.stdout).unwrap();
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(other.lines().nth(1).unwrap());
+    terminal.expect("Rejected: Share belongs to another set than the first one");
+    let output = sharks(&["split", "-k", "2", "-n", "5"], b"launch codes");
+    let other = String::from_utf8(output.stdout).unwrap();
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(other.lines().nth(1).unwrap());
+    terminal.expect("Rejected: Share was dealt with a threshold of 2 instead of 3");
+
+    terminal.expect("Share 2 of 3: ");
+    terminal.type_line(&shares[3]);
+    terminal.expect("Accepted share x = 4 (2/3)");
+    terminal.expect("Share 3 of 3: ");
+    terminal.type_line(&shares[4]);
+    terminal.expect("Accepted share x = 5 (3/3)");
+
+    terminal.expect("Secret recovered:");
+    terminal.expect("Press Enter to clear the screen.");
+    terminal.type_line("");
+    let (success, screen) = terminal.finish();
+    assert!(success);
+
+    // Shares were never echoed, the secret was shown and then wiped
+    assert!(shares.iter().all(|share| !screen.contains(share.as_str())));
+    let shown = screen.find("6c61756e636820636f646573").unwrap();
+    assert!(screen[shown..].contains("\x1b[3J"));
+}
+
+#[cfg(unix)]
+#[test]
+fn ceremony_aborts_at_end_of_input() {
+    let mut terminal = Terminal::spawn(&["ceremony", "-k", "2"]);
+    terminal.expect("Share 1 of 2: ");
+    terminal.master.write_all(&[4]).unwrap();
+    let (success, screen) = terminal.finish();
+    assert!(!success);
+    assert!(screen.contains("Ceremony aborted before the threshold was met"));
+}
+
+#[cfg(unix)]
+#[test]
+fn ceremony_restores_terminal_when_interrupted() {
+    let output = sharks(&["split", "-k", "1", "-n", "1"], b"launch codes");
+    let share = String::from_utf8(output.stdout).unwrap();
+
+    // Interrupted while a share is being entered
+    let mut terminal = Terminal::spawn(&["ceremony", "-k", "1"]);
+    terminal.expect("Share 1 of 1: ");
+    assert!(!terminal.echoes());
+    terminal.master.write_all(&[3]).unwrap();
+    terminal.child.wait().unwrap();
+    assert!(terminal.echoes());
+
+    // Interrupted while the secret is shown
+    let mut terminal = Terminal::spawn(&["ceremony", "-k", "1"]);
+    terminal.expect("Share 1 of 1: ");
+    terminal.type_line(share.trim());
+    terminal.expect("Press Enter to clear the screen.");
+    terminal.master.write_all(&[3]).unwrap();
+    let echoes = {
+        terminal.child.wait().unwrap();
+        terminal.echoes()
+    };
+    let (success, screen) = terminal.finish();
+    assert!(!success);
+    assert!(echoes);
+    let shown = screen.find("6c61756e636820636f646573").unwrap();
+    assert!(screen[shown..].contains("\x1b[3J"));
+}
@@ -0,0 +1,63 @@
+/* Exercises the C API through the generated header, run by tests/ffi.rs */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "sharks.h"
+
+#define CHECK(condition)                                                   \
+    do {                                                                   \
+        if (!(condition)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                           \
+            return 1;                                                      \
+        }                                                                  \
+    } while (0)
+
+int main(void) {
+    const uint8_t secret[] = "hsm master key";
+    SharksShare *shares[5] = {NULL};
+    uint8_t *bytes = NULL;
+    size_t len = 0;
+    uint8_t *recovered = NULL;
+    size_t recovered_len = 0;
+    size_t i;
+
+    CHECK(sharks_split(3, secret, sizeof(secret), 5, shares) == SHARKS_ERROR_OK);
+    for (i = 0; i < 5; i++) {
+        CHECK(shares[i] != NULL);
+    }
+
+    /* Round trip the last share through its serialization */
+    CHECK(sharks_share_to_bytes(shares[4], &bytes, &len) == SHARKS_ERROR_OK);
+    CHECK(len == sizeof(secret) + 1);
+    sharks_share_free(shares[4]);
+    shares[4] = NULL;
+    CHECK(sharks_share_from_bytes(bytes, len, &shares[4]) == SHARKS_ERROR_OK);
+    sharks_bytes_free(bytes, len);
+
+    {
+        const SharksShare *subset[3] = {shares[0], shares[2], shares[4]};
+        CHECK(sharks_combine(3, subset, 3, &recovered, &recovered_len) == SHARKS_ERROR_OK);
+        CHECK(recovered_len == sizeof(secret));
+        CHECK(memcmp(recovered, secret, sizeof(secret)) == 0);
+        sharks_bytes_free(recovered, recovered_len);
+
+        recovered = NULL;
+        CHECK(sharks_combine(3, subset, 2, &recovered, &recovered_len) == SHARKS_ERROR_NOT_ENOUGH_SHARES);
+        CHECK(recovered == NULL);
+        CHECK(sharks_combine(0, subset, 1, &recovered, &recovered_len) == SHARKS_ERROR_INVALID_ARGUMENT);
+        CHECK(recovered == NULL);
+    }
+
+    CHECK(sharks_split(3, secret, 0, 5, shares) == SHARKS_ERROR_INVALID_ARGUMENT);
+    CHECK(sharks_split(3, NULL, sizeof(secret), 5, shares) == SHARKS_ERROR_NULL_POINTER);
+    CHECK(sharks_share_from_bytes(secret, 1, &shares[0]) == SHARKS_ERROR_INVALID_SHARE);
+
+    for (i = 0; i < 5; i++) {
+        sharks_share_free(shares[i]);
+    }
+    sharks_share_free(NULL);
+    sharks_bytes_free(NULL, 0);
+    return 0;
+}
@@ -0,0 +1,61 @@
+// Checks the C header against the `ffi` module, and runs a C program against the static library.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[test]
+fn header_is_up_to_date() {
+    let config =
+        cbindgen::Config::from_file(Path::new(MANIFEST_DIR).join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::generate_with_config(MANIFEST_DIR, config)
+        .unwrap()
+        .write(&mut header);
+    assert_eq!(
+        fs::read_to_string(Path::new(MANIFEST_DIR).join("include/sharks.h")).unwrap(),
+        String::from_utf8(header).unwrap(),
+        "include/sharks.h is stale, regenerate it with `cbindgen --config cbindgen.toml --output include/sharks.h`"
+    );
+}
+
+// Builds the crate as a static library, in its own target directory
+fn build_static_library(target_dir: &Path) -> PathBuf {
+    let status = Command::new(option_env!("CARGO").unwrap_or("cargo"))
+        .args(["rustc", "--quiet", "--lib", "--features", "ffi"])
+        .args(["--crate-type", "staticlib", "--target-dir"])
+        .arg(target_dir)
+        .current_dir(MANIFEST_DIR)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    target_dir.join("debug/libsharks.a")
+}
+
+#[cfg(unix)]
+#[test]
+fn c_program_works() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let library = build_static_library(&target_dir);
+    let program = target_dir.join("sharks_test");
+
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+        .args(["-std=c99", "-Wall", "-Werror", "-I"])
+        .arg(Path::new(MANIFEST_DIR).join("include"))
+        .arg(Path::new(MANIFEST_DIR).join("tests/ffi.c"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,66 @@
+# Tests of the Python bindings, run with `maturin develop --features python && pytest tests/python`.
+import pytest
+
+import sharks
+
+
+def test_split_and_combine_work():
+    shares = sharks.split(b"recovery key", 3, 5)
+    assert len(shares) == 5
+    assert [share.x for share in shares] == [1, 2, 3, 4, 5]
+    assert all(len(share) == 12 for share in shares)
+
+    assert sharks.combine(shares[2:], 3) == b"recovery key"
+    assert sharks.combine([shares[4], shares[0], shares[2]], 3) == b"recovery key"
+    assert sharks.combine(shares[:3], 3) == b"recovery key"
+
+
+def test_serialization_works():
+    share = sharks.Share.from_bytes(bytes([1, 2, 3]))
+    assert share.x == 1
+    assert len(share) == 2
+    assert bytes(share) == bytes([1, 2, 3])
+    assert share.to_bytes() == bytes([1, 2, 3])
+    assert share == sharks.Share.from_bytes(bytes(share))
+    assert share != sharks.Share.from_bytes(bytes([1, 2, 4]))
+    assert repr(share) == "Share(x=1, len=2)"
+
+    shares = [sharks.Share.from_bytes(bytes(s)) for s in sharks.split(b"secret", 2, 2)]
+    assert sharks.combine(shares, 2) == b"secret"
+
+
+def test_exceptions_are_typed():
+    assert issubclass(sharks.InvalidArgument, sharks.SharksError)
+    assert issubclass(sharks.SharksError, Exception)
+
+    with pytest.raises(sharks.InvalidArgument):
+        sharks.split(b"secret", 3, 2)
+    with pytest.raises(sharks.InvalidArgument):
+        sharks.split(b"secret", 0, 2)
+    with pytest.raises(sharks.InvalidArgument):
+        sharks.split(b"", 2, 2)
+    with pytest.raises(OverflowError):
+        sharks.split(b"secret", 2, 256)
+
+    shares = sharks.split(b"secret", 3, 3)
+    with pytest.raises(sharks.NotEnoughShares):
+        sharks.combine(shares[:2], 3)
+    with pytest.raises(sharks.NotEnoughShares):
+        sharks.combine([shares[0], shares[0], shares[1]], 3)
+    with pytest.raises(sharks.NotEnoughShares):
+        sharks.combine([], 1)
+
+    # The threshold is required, and can not be zero
+    with pytest.raises(TypeError):
+        sharks.combine(shares)
+    with pytest.raises(sharks.InvalidArgument):
+        sharks.combine(shares, 0)
+
+    other = sharks.split(b"longer secret", 2, 2)
+    with pytest.raises(sharks.InconsistentShares):
+        sharks.combine([shares[0], other[1]], 2)
+
+    with pytest.raises(sharks.InvalidShare):
+        sharks.Share.from_bytes(b"\x01")
+    with pytest.raises(TypeError):
+        sharks.combine([bytes(shares[0])], 1)
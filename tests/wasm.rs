@@ -0,0 +1,45 @@
+// Headless tests of the WebAssembly bindings, run under Node with `wasm-pack test --node --features wasm`.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Uint8Array};
+use sharks::wasm::{combine, split, split_hex};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn split_and_combine_work() {
+    let shares = split(b"recovery key", 3, 5).unwrap();
+    assert_eq!(shares.length(), 5);
+    let share = Uint8Array::from(shares.get(0));
+    assert_eq!(share.length(), 13);
+
+    let subset: Array = shares.slice(2, 5);
+    let secret = combine(subset, 3).unwrap();
+    assert_eq!(secret.to_vec(), b"recovery key");
+    assert!(combine(shares.slice(0, 2), 3).is_err());
+}
+
+#[wasm_bindgen_test]
+fn hex_shares_work() {
+    let shares = split_hex(&[1, 2, 3], 2, 3).unwrap();
+    assert!(shares.get(1).as_string().unwrap().starts_with("02"));
+
+    // Hex and binary shares can be mixed
+    let mixed = Array::of2(&shares.get(0), &Uint8Array::from(&[2u8, 2, 4, 6][..]));
+    assert!(combine(mixed, 2).is_ok());
+    let secret = combine(shares, 2).unwrap();
+    assert_eq!(secret.to_vec(), [1, 2, 3]);
+}
+
+#[wasm_bindgen_test]
+fn invalid_input_throws() {
+    assert!(split(&[], 2, 3).is_err());
+    assert!(split(&[1], 3, 2).is_err());
+    let invalid = Array::of1(&JsValue::from_f64(1.0));
+    assert!(combine(invalid, 1).is_err());
+    let not_hex = Array::of1(&JsValue::from_str("0g12"));
+    assert!(combine(not_hex, 1).is_err());
+    let shares = split(&[1, 2, 3], 1, 1).unwrap();
+    assert!(combine(shares.clone(), 1).is_ok());
+    assert!(combine(shares, 0).is_err());
+}